@@ -0,0 +1,248 @@
+use {
+    crate::{
+        errors::UtilErrors, match_collection_creator, metadata_reader::MetadataReader, prelude::*,
+        ValidateCollectionCreatorOutput,
+    },
+    mpl_token_metadata::state::{Collection, Creator},
+    spl_pod::{bytemuck::pod_from_bytes, optional_keys::OptionalNonZeroPubkey, primitives::PodU64},
+    spl_token_2022::{
+        extension::{
+            metadata_pointer::MetadataPointer, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensions,
+        },
+        state::Mint,
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+    spl_type_length_value::state::{TlvState, TlvStateBorrowed},
+};
+
+// Token-2022 0.9, the last release on Solana 1.16, predates token groups, so
+// neither their extension types nor `spl-token-group-interface` are available
+// to take them from. The discriminants and layouts below are those of
+// Token-2022 1.0.
+
+/// `ExtensionType::GroupMemberPointer`.
+const GROUP_MEMBER_POINTER_EXTENSION: u16 = 22;
+/// `ExtensionType::TokenGroupMember`.
+const TOKEN_GROUP_MEMBER_EXTENSION: u16 = 23;
+
+/// The `GroupMemberPointer` extension: where the group membership of a mint
+/// is stored.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct GroupMemberPointer {
+    authority: OptionalNonZeroPubkey,
+    member_address: OptionalNonZeroPubkey,
+}
+
+/// The `TokenGroupMember` of `spl-token-group-interface`.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct TokenGroupMember {
+    mint: Pubkey,
+    group: Pubkey,
+    member_number: PodU64,
+}
+
+/// Where the metadata of an asset was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssetMetadataSource {
+    /// A Token Metadata `Metadata` account.
+    Metaplex,
+    /// The Token-2022 token-metadata extension the mint's metadata pointer
+    /// refers to.
+    Token2022,
+}
+
+/// The collection of a Metaplex asset, or the token group of a Token-2022 one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AssetGroup {
+    pub address: Pubkey,
+    /// Token group membership read from a mint is always signed for by the
+    /// group's update authority, so only Metaplex collections can be
+    /// unverified.
+    pub verified: bool,
+}
+
+/// Metadata of an asset, whichever standard it is stored with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetMetadata {
+    pub source: AssetMetadataSource,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub update_authority: Option<Pubkey>,
+    pub group: Option<AssetGroup>,
+    /// Only Metaplex metadata has creators.
+    pub creators: Vec<Creator>,
+}
+
+impl AssetMetadata {
+    /// Reads the metadata of `mint` from `metadata`, which is either its Token
+    /// Metadata account or the account its Token-2022 metadata pointer refers
+    /// to, possibly the mint itself.
+    pub fn load(mint: &AccountInfo, metadata: &AccountInfo) -> Result<Self> {
+        if *metadata.owner == mpl_token_metadata::ID {
+            let data = metadata.try_borrow_data()?;
+            let reader = MetadataReader::new(&data)?;
+            if reader.mint() != *mint.key {
+                msg!(
+                    "Metadata {} does not belong to mint {}",
+                    metadata.key,
+                    mint.key
+                );
+                return Err(UtilErrors::InvalidMetadata.into());
+            }
+            Self::from_metaplex(&reader)
+        } else {
+            Self::from_token_2022(mint, metadata)
+        }
+    }
+
+    pub fn from_metaplex(metadata: &MetadataReader) -> Result<Self> {
+        Ok(Self {
+            source: AssetMetadataSource::Metaplex,
+            mint: metadata.mint(),
+            name: metadata.name()?.trim_end_matches('\0').to_string(),
+            symbol: metadata.symbol()?.trim_end_matches('\0').to_string(),
+            uri: metadata.uri()?.trim_end_matches('\0').to_string(),
+            update_authority: Some(metadata.update_authority()),
+            group: metadata.collection()?.map(|collection| AssetGroup {
+                address: collection.key,
+                verified: collection.verified,
+            }),
+            creators: metadata
+                .creators()?
+                .map(|list| list.iter().collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn from_token_2022(mint: &AccountInfo, metadata: &AccountInfo) -> Result<Self> {
+        if *mint.owner != spl_token_2022::ID {
+            return Err(UtilErrors::InvalidTokenProgram.into());
+        }
+        let mint_data = mint.try_borrow_data()?;
+        let state = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+        let pointer = state
+            .get_extension::<MetadataPointer>()
+            .map_err(|_| UtilErrors::InvalidMetadata)?;
+        if Option::<Pubkey>::from(pointer.metadata_address) != Some(*metadata.key) {
+            msg!(
+                "Mint {} does not point to metadata {}",
+                mint.key,
+                metadata.key
+            );
+            return Err(UtilErrors::InvalidMetadata.into());
+        }
+
+        let token_metadata = if metadata.key == mint.key {
+            state.get_variable_len_extension::<TokenMetadata>()?
+        } else {
+            read_token_metadata(&metadata.try_borrow_data()?)?
+        };
+        if token_metadata.mint != *mint.key {
+            return Err(UtilErrors::InvalidMetadata.into());
+        }
+
+        let group = read_group_member(mint.key, state.get_tlv_data())?;
+
+        Ok(Self {
+            source: AssetMetadataSource::Token2022,
+            mint: *mint.key,
+            name: token_metadata.name,
+            symbol: token_metadata.symbol,
+            uri: token_metadata.uri,
+            update_authority: token_metadata.update_authority.into(),
+            group,
+            creators: vec![],
+        })
+    }
+
+    /// Same matching as `validate_collection_creator`, with the token group
+    /// of Token-2022 assets standing in for the collection.
+    pub fn validate_collection_creator(
+        &self,
+        collections: &[Pubkey],
+        creators: &[Pubkey],
+    ) -> Result<ValidateCollectionCreatorOutput> {
+        match_collection_creator(
+            self.group.map(|group| Collection {
+                verified: group.verified,
+                key: group.address,
+            }),
+            Some(self.creators.iter().cloned()),
+            collections,
+            creators,
+        )
+    }
+}
+
+/// Reads the token group of the Token-2022 mint `mint` from its extensions.
+///
+/// Token-2022 only writes a `TokenGroupMember` into a mint whose group member
+/// pointer refers to the mint itself, and only with the signature of the
+/// group's update authority, so that membership is verified. Membership kept
+/// in another account is up to the program owning it, so mints pointing
+/// elsewhere are rejected rather than reported as members.
+fn read_group_member(mint: &Pubkey, tlv_data: &[u8]) -> Result<Option<AssetGroup>> {
+    let Some(pointer) = find_extension(tlv_data, GROUP_MEMBER_POINTER_EXTENSION)? else {
+        return Ok(None);
+    };
+    let pointer =
+        pod_from_bytes::<GroupMemberPointer>(pointer).map_err(|_| UtilErrors::InvalidMetadata)?;
+    match Option::<Pubkey>::from(pointer.member_address) {
+        None => return Ok(None),
+        Some(member_address) if member_address != *mint => {
+            msg!(
+                "Mint {} keeps its group membership in {}",
+                mint,
+                member_address
+            );
+            return Err(UtilErrors::UnsupportedGroupMemberPointer.into());
+        }
+        Some(_) => {}
+    }
+
+    let Some(member) = find_extension(tlv_data, TOKEN_GROUP_MEMBER_EXTENSION)? else {
+        return Ok(None);
+    };
+    let member =
+        pod_from_bytes::<TokenGroupMember>(member).map_err(|_| UtilErrors::InvalidMetadata)?;
+    Ok((member.mint == *mint).then_some(AssetGroup {
+        address: member.group,
+        verified: true,
+    }))
+}
+
+/// Returns the value of the Token-2022 extension of type `extension_type`,
+/// even one `ExtensionType` does not know. Fails on a truncated entry.
+fn find_extension(tlv_data: &[u8], extension_type: u16) -> Result<Option<&[u8]>> {
+    let uninitialized = u16::from(ExtensionType::Uninitialized);
+    let mut offset = 0;
+    while let Some(header) = tlv_data.get(offset..offset + 4) {
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let length = u16::from_le_bytes([header[2], header[3]]) as usize;
+        if entry_type == uninitialized {
+            return Ok(None);
+        }
+        let value = tlv_data
+            .get(offset + 4..offset + 4 + length)
+            .ok_or(UtilErrors::InvalidMetadata)?;
+        if entry_type == extension_type {
+            return Ok(Some(value));
+        }
+        offset += 4 + length;
+    }
+    Ok(None)
+}
+
+/// Reads `TokenMetadata` out of an account implementing the token-metadata
+/// interface.
+fn read_token_metadata(data: &[u8]) -> Result<TokenMetadata> {
+    TlvStateBorrowed::unpack(data)
+        .and_then(|state| state.get_first_variable_len_value::<TokenMetadata>())
+        .map_err(|_| UtilErrors::InvalidMetadata.into())
+}
@@ -0,0 +1,34 @@
+pub use {
+    mpl_token_auth_rules::payload::{Payload, PayloadType, ProofInfo, SeedsVec},
+    mpl_token_metadata::{processor::AuthorizationData, state::PayloadKey},
+};
+
+use crate::prelude::*;
+
+/// Builds the `AuthorizationData` passed to programmable NFT instructions
+/// from a list of payload entries.
+pub fn authorization_data(
+    entries: impl IntoIterator<Item = (PayloadKey, PayloadType)>,
+) -> AuthorizationData {
+    let mut payload = Payload::new();
+    for (key, value) in entries {
+        payload.insert(key.to_string(), value);
+    }
+    AuthorizationData::new(payload)
+}
+
+pub fn pubkey_entry(key: PayloadKey, pubkey: Pubkey) -> (PayloadKey, PayloadType) {
+    (key, PayloadType::Pubkey(pubkey))
+}
+
+pub fn seeds_entry(key: PayloadKey, seeds: Vec<Vec<u8>>) -> (PayloadKey, PayloadType) {
+    (key, PayloadType::Seeds(SeedsVec::new(seeds)))
+}
+
+pub fn merkle_proof_entry(key: PayloadKey, proof: Vec<[u8; 32]>) -> (PayloadKey, PayloadType) {
+    (key, PayloadType::MerkleProof(ProofInfo::new(proof)))
+}
+
+pub fn amount_entry(amount: u64) -> (PayloadKey, PayloadType) {
+    (PayloadKey::Amount, PayloadType::Number(amount))
+}
@@ -1,4 +1,4 @@
-use anchor_lang::solana_program::program_memory::sol_memcpy;
+use solana_program::program_memory::sol_memcpy;
 use std::cmp;
 use std::io::{self, Write};
 
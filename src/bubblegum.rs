@@ -0,0 +1,795 @@
+use {
+    crate::{
+        compression::{ConcurrentMerkleTreeReader, SPL_ACCOUNT_COMPRESSION_ID},
+        errors::UtilErrors,
+        match_collection_creator,
+        metadata::invoke_instruction,
+        metadata_reader::CreatorsReader,
+        prelude::*,
+        ValidateCollectionCreatorOutput,
+    },
+    mpl_token_metadata::state::{Collection, Creator, MAX_CREATOR_LEN},
+    solana_program::keccak::hashv,
+};
+
+pub use mpl_bubblegum::{
+    self,
+    instructions::{
+        BurnInstructionArgs, DecompressV1InstructionArgs, DelegateInstructionArgs,
+        MintToCollectionV1InstructionArgs, RedeemInstructionArgs, TransferInstructionArgs,
+        VerifyCollectionInstructionArgs,
+    },
+    types::MetadataArgs,
+    ID as BUBBLEGUM_PROGRAM_ID,
+};
+
+const LEAF_SCHEMA_V1: u8 = 1;
+
+/// Returns the asset id of the compressed NFT minted as `nonce` in `merkle_tree`.
+pub fn find_asset_id(merkle_tree: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &BUBBLEGUM_PROGRAM_ID,
+    )
+}
+
+/// Returns the PDA Bubblegum signs Token Metadata collection CPIs with.
+pub fn find_bubblegum_signer() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"collection_cpi"], &BUBBLEGUM_PROGRAM_ID)
+}
+
+/// Returns the tree config PDA, which Bubblegum sets as the tree authority.
+pub fn find_tree_config(merkle_tree: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &BUBBLEGUM_PROGRAM_ID)
+}
+
+pub fn hash_creators(creators: impl IntoIterator<Item = Creator>) -> [u8; 32] {
+    let data = creators
+        .into_iter()
+        .flat_map(|creator| {
+            let mut bytes = [0u8; MAX_CREATOR_LEN];
+            bytes[..32].copy_from_slice(creator.address.as_ref());
+            bytes[32] = creator.verified as u8;
+            bytes[33] = creator.share;
+            bytes
+        })
+        .collect::<Vec<u8>>();
+    hashv(&[&data]).0
+}
+
+pub fn hash_metadata(metadata: &MetadataArgsReader) -> [u8; 32] {
+    let metadata_args_hash = hashv(&[metadata.data()]).0;
+    hashv(&[
+        &metadata_args_hash,
+        &metadata.seller_fee_basis_points().to_le_bytes(),
+    ])
+    .0
+}
+
+/// The `V1` leaf Bubblegum appends to the tree for every compressed NFT.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeafSchema {
+    pub id: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub nonce: u64,
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+}
+
+impl LeafSchema {
+    pub fn hash(&self) -> [u8; 32] {
+        hashv(&[
+            &[LEAF_SCHEMA_V1],
+            self.id.as_ref(),
+            self.owner.as_ref(),
+            self.delegate.as_ref(),
+            &self.nonce.to_le_bytes(),
+            &self.data_hash,
+            &self.creator_hash,
+        ])
+        .0
+    }
+}
+
+/// Borrow-based view over Borsh encoded Bubblegum `MetadataArgs`, as passed
+/// in instruction data.
+#[derive(Clone, Copy)]
+pub struct MetadataArgsReader<'a> {
+    data: &'a [u8],
+    seller_fee_basis_points_offset: usize,
+    collection_offset: usize,
+    creators_offset: usize,
+}
+
+impl<'a> MetadataArgsReader<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        let mut offset = 0;
+        for _ in 0..3 {
+            let len = u32::from_le_bytes(array_at(data, offset)?) as usize;
+            offset += 4 + len;
+        }
+        let seller_fee_basis_points_offset = offset;
+        // seller_fee_basis_points, primary_sale_happened and is_mutable
+        offset += 4;
+        // edition_nonce and token_standard
+        offset = skip_option(data, offset, 1)?;
+        offset = skip_option(data, offset, 1)?;
+        let collection_offset = offset;
+        offset = skip_option(data, offset, 33)?;
+        // uses and token_program_version
+        offset = skip_option(data, offset, 17)? + 1;
+        let creators_offset = offset;
+        let count = u32::from_le_bytes(array_at(data, offset)?) as usize;
+        let end = count
+            .checked_mul(MAX_CREATOR_LEN)
+            .and_then(|len| len.checked_add(offset + 4))
+            .ok_or(UtilErrors::InvalidMetadata)?;
+        if end != data.len() {
+            return Err(UtilErrors::InvalidMetadata.into());
+        }
+
+        Ok(Self {
+            data,
+            seller_fee_basis_points_offset,
+            collection_offset,
+            creators_offset,
+        })
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn seller_fee_basis_points(&self) -> u16 {
+        u16::from_le_bytes(array_at(self.data, self.seller_fee_basis_points_offset).unwrap())
+    }
+
+    pub fn collection(&self) -> Option<Collection> {
+        if self.data[self.collection_offset] == 0 {
+            return None;
+        }
+        Some(Collection {
+            verified: self.data[self.collection_offset + 1] != 0,
+            key: Pubkey::new_from_array(array_at(self.data, self.collection_offset + 2).unwrap()),
+        })
+    }
+
+    pub fn creators(&self) -> CreatorsReader<'a> {
+        CreatorsReader::new(&self.data[self.creators_offset + 4..])
+    }
+}
+
+fn array_at<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .map(|bytes| bytes.try_into().unwrap())
+        .ok_or_else(|| UtilErrors::InvalidMetadata.into())
+}
+
+fn skip_option(data: &[u8], offset: usize, len: usize) -> Result<usize> {
+    match data.get(offset) {
+        Some(0) => Ok(offset + 1),
+        Some(1) if data.len() >= offset + 1 + len => Ok(offset + 1 + len),
+        _ => Err(UtilErrors::InvalidMetadata.into()),
+    }
+}
+
+/// A compressed NFT as described by the caller, to be proved against its tree.
+#[derive(Clone, Copy)]
+pub struct CompressedNft<'a> {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub nonce: u64,
+    pub index: u32,
+    pub metadata: MetadataArgsReader<'a>,
+}
+
+impl<'a> CompressedNft<'a> {
+    pub fn leaf_schema(&self, merkle_tree: &Pubkey) -> LeafSchema {
+        LeafSchema {
+            id: find_asset_id(merkle_tree, self.nonce).0,
+            owner: self.owner,
+            delegate: self.delegate,
+            nonce: self.nonce,
+            data_hash: hash_metadata(&self.metadata),
+            creator_hash: hash_creators(self.metadata.creators().iter()),
+        }
+    }
+
+    /// Proves the leaf of the NFT against a Bubblegum owned concurrent merkle
+    /// tree. `root` may be any root still in the tree's changelog buffer.
+    pub fn verify(
+        &self,
+        merkle_tree: &AccountInfo,
+        root: [u8; 32],
+        proof: &[[u8; 32]],
+    ) -> Result<LeafSchema> {
+        if *merkle_tree.owner != SPL_ACCOUNT_COMPRESSION_ID {
+            return Err(UtilErrors::InvalidMerkleTree.into());
+        }
+        let data = merkle_tree.try_borrow_data()?;
+        let tree = ConcurrentMerkleTreeReader::new(&data)?;
+        if tree.authority() != find_tree_config(merkle_tree.key).0 {
+            msg!(
+                "Merkle tree {} is not managed by Bubblegum",
+                merkle_tree.key
+            );
+            return Err(UtilErrors::InvalidMerkleTree.into());
+        }
+
+        let leaf_schema = self.leaf_schema(merkle_tree.key);
+        tree.prove_leaf(root, leaf_schema.hash(), proof, self.index)?;
+        Ok(leaf_schema)
+    }
+}
+
+/// Reads a proof passed as the keys of the remaining accounts, like the
+/// account compression and Bubblegum programs expect it.
+pub fn proof_from_accounts(accounts: &[AccountInfo]) -> Vec<[u8; 32]> {
+    accounts
+        .iter()
+        .map(|account| account.key.to_bytes())
+        .collect()
+}
+
+/// Compressed counterpart of `validate_collection_creator`: proves `nft`
+/// against `merkle_tree` before matching its collection and creators.
+pub fn validate_compressed_collection_creator(
+    merkle_tree: &AccountInfo,
+    root: [u8; 32],
+    proof: &[[u8; 32]],
+    nft: &CompressedNft,
+    collections: &[Pubkey],
+    creators: &[Pubkey],
+) -> Result<ValidateCollectionCreatorOutput> {
+    nft.verify(merkle_tree, root, proof)?;
+    match_collection_creator(
+        nft.metadata.collection(),
+        Some(nft.metadata.creators().iter()),
+        collections,
+        creators,
+    )
+}
+
+fn proof_metas(proof: &[AccountInfo]) -> Vec<AccountMeta> {
+    proof
+        .iter()
+        .map(|node| AccountMeta::new_readonly(node.key(), false))
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct TransferAccounts<'info> {
+    pub tree_config: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub leaf_delegate: AccountInfo<'info>,
+    pub new_leaf_owner: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub proof: Vec<AccountInfo<'info>>,
+    pub delegate_as_signer: bool,
+}
+
+impl<'info> TransferAccounts<'info> {
+    pub fn invoke(
+        &self,
+        args: TransferInstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let transfer_ix = mpl_bubblegum::instructions::Transfer {
+            tree_config: self.tree_config.key(),
+            leaf_owner: (self.leaf_owner.key(), !self.delegate_as_signer),
+            leaf_delegate: (self.leaf_delegate.key(), self.delegate_as_signer),
+            new_leaf_owner: self.new_leaf_owner.key(),
+            merkle_tree: self.merkle_tree.key(),
+            log_wrapper: self.log_wrapper.key(),
+            compression_program: self.compression_program.key(),
+            system_program: self.system_program.key(),
+        }
+        .instruction_with_remaining_accounts(args, &proof_metas(&self.proof));
+
+        let mut account_infos = vec![
+            self.tree_config.clone(),
+            self.leaf_owner.clone(),
+            self.leaf_delegate.clone(),
+            self.new_leaf_owner.clone(),
+            self.merkle_tree.clone(),
+            self.log_wrapper.clone(),
+            self.compression_program.clone(),
+            self.system_program.clone(),
+        ];
+        account_infos.extend(self.proof.iter().cloned());
+
+        invoke_instruction(&transfer_ix, &account_infos, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct BurnAccounts<'info> {
+    pub tree_config: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub leaf_delegate: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub proof: Vec<AccountInfo<'info>>,
+    pub delegate_as_signer: bool,
+}
+
+impl<'info> BurnAccounts<'info> {
+    pub fn invoke(
+        &self,
+        args: BurnInstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let burn_ix = mpl_bubblegum::instructions::Burn {
+            tree_config: self.tree_config.key(),
+            leaf_owner: (self.leaf_owner.key(), !self.delegate_as_signer),
+            leaf_delegate: (self.leaf_delegate.key(), self.delegate_as_signer),
+            merkle_tree: self.merkle_tree.key(),
+            log_wrapper: self.log_wrapper.key(),
+            compression_program: self.compression_program.key(),
+            system_program: self.system_program.key(),
+        }
+        .instruction_with_remaining_accounts(args, &proof_metas(&self.proof));
+
+        let mut account_infos = vec![
+            self.tree_config.clone(),
+            self.leaf_owner.clone(),
+            self.leaf_delegate.clone(),
+            self.merkle_tree.clone(),
+            self.log_wrapper.clone(),
+            self.compression_program.clone(),
+            self.system_program.clone(),
+        ];
+        account_infos.extend(self.proof.iter().cloned());
+
+        invoke_instruction(&burn_ix, &account_infos, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct DelegateAccounts<'info> {
+    pub tree_config: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub previous_leaf_delegate: AccountInfo<'info>,
+    pub new_leaf_delegate: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub proof: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> DelegateAccounts<'info> {
+    pub fn invoke(
+        &self,
+        args: DelegateInstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let delegate_ix = mpl_bubblegum::instructions::Delegate {
+            tree_config: self.tree_config.key(),
+            leaf_owner: self.leaf_owner.key(),
+            previous_leaf_delegate: self.previous_leaf_delegate.key(),
+            new_leaf_delegate: self.new_leaf_delegate.key(),
+            merkle_tree: self.merkle_tree.key(),
+            log_wrapper: self.log_wrapper.key(),
+            compression_program: self.compression_program.key(),
+            system_program: self.system_program.key(),
+        }
+        .instruction_with_remaining_accounts(args, &proof_metas(&self.proof));
+
+        let mut account_infos = vec![
+            self.tree_config.clone(),
+            self.leaf_owner.clone(),
+            self.previous_leaf_delegate.clone(),
+            self.new_leaf_delegate.clone(),
+            self.merkle_tree.clone(),
+            self.log_wrapper.clone(),
+            self.compression_program.clone(),
+            self.system_program.clone(),
+        ];
+        account_infos.extend(self.proof.iter().cloned());
+
+        invoke_instruction(&delegate_ix, &account_infos, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct RedeemAccounts<'info> {
+    pub tree_config: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub leaf_delegate: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub voucher: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub proof: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> RedeemAccounts<'info> {
+    pub fn invoke(
+        &self,
+        args: RedeemInstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let redeem_ix = mpl_bubblegum::instructions::Redeem {
+            tree_config: self.tree_config.key(),
+            leaf_owner: self.leaf_owner.key(),
+            leaf_delegate: self.leaf_delegate.key(),
+            merkle_tree: self.merkle_tree.key(),
+            voucher: self.voucher.key(),
+            log_wrapper: self.log_wrapper.key(),
+            compression_program: self.compression_program.key(),
+            system_program: self.system_program.key(),
+        }
+        .instruction_with_remaining_accounts(args, &proof_metas(&self.proof));
+
+        let mut account_infos = vec![
+            self.tree_config.clone(),
+            self.leaf_owner.clone(),
+            self.leaf_delegate.clone(),
+            self.merkle_tree.clone(),
+            self.voucher.clone(),
+            self.log_wrapper.clone(),
+            self.compression_program.clone(),
+            self.system_program.clone(),
+        ];
+        account_infos.extend(self.proof.iter().cloned());
+
+        invoke_instruction(&redeem_ix, &account_infos, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct DecompressAccounts<'info> {
+    pub voucher: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub mint_authority: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub master_edition: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+    pub token_metadata_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+}
+
+impl<'info> DecompressAccounts<'info> {
+    pub fn invoke(
+        &self,
+        args: DecompressV1InstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let decompress_ix = mpl_bubblegum::instructions::DecompressV1 {
+            voucher: self.voucher.key(),
+            leaf_owner: self.leaf_owner.key(),
+            token_account: self.token_account.key(),
+            mint: self.mint.key(),
+            mint_authority: self.mint_authority.key(),
+            metadata_account: self.metadata.key(),
+            master_edition: self.master_edition.key(),
+            system_program: self.system_program.key(),
+            sysvar_rent: self.rent.key(),
+            token_metadata_program: self.token_metadata_program.key(),
+            token_program: self.token_program.key(),
+            associated_token_program: self.associated_token_program.key(),
+            log_wrapper: self.log_wrapper.key(),
+        }
+        .instruction(args);
+
+        let account_infos = [
+            self.voucher.clone(),
+            self.leaf_owner.clone(),
+            self.token_account.clone(),
+            self.mint.clone(),
+            self.mint_authority.clone(),
+            self.metadata.clone(),
+            self.master_edition.clone(),
+            self.system_program.clone(),
+            self.rent.clone(),
+            self.token_metadata_program.clone(),
+            self.token_program.clone(),
+            self.associated_token_program.clone(),
+            self.log_wrapper.clone(),
+        ];
+
+        invoke_instruction(&decompress_ix, &account_infos, signer_seeds)
+    }
+}
+
+/// Accounts of `verify_collection`. Without a `collection_authority_record`,
+/// Bubblegum expects its own program account in that slot, so
+/// `bubblegum_program` is always passed along.
+#[derive(Clone)]
+pub struct VerifyCollectionAccounts<'info> {
+    pub tree_config: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub leaf_delegate: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub tree_creator_or_delegate: AccountInfo<'info>,
+    pub collection_authority: AccountInfo<'info>,
+    pub collection_authority_record: Option<AccountInfo<'info>>,
+    pub collection_mint: AccountInfo<'info>,
+    pub collection_metadata: AccountInfo<'info>,
+    pub collection_edition: AccountInfo<'info>,
+    pub bubblegum_signer: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub token_metadata_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub bubblegum_program: AccountInfo<'info>,
+    pub proof: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> VerifyCollectionAccounts<'info> {
+    /// Verifies the collection of an already minted leaf.
+    pub fn invoke(
+        &self,
+        args: VerifyCollectionInstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let verify_ix = mpl_bubblegum::instructions::VerifyCollection {
+            tree_config: self.tree_config.key(),
+            leaf_owner: self.leaf_owner.key(),
+            leaf_delegate: self.leaf_delegate.key(),
+            merkle_tree: self.merkle_tree.key(),
+            payer: self.payer.key(),
+            tree_creator_or_delegate: self.tree_creator_or_delegate.key(),
+            collection_authority: self.collection_authority.key(),
+            collection_authority_record_pda: self
+                .collection_authority_record
+                .as_ref()
+                .map(|record| record.key()),
+            collection_mint: self.collection_mint.key(),
+            collection_metadata: self.collection_metadata.key(),
+            collection_edition: self.collection_edition.key(),
+            bubblegum_signer: self.bubblegum_signer.key(),
+            log_wrapper: self.log_wrapper.key(),
+            compression_program: self.compression_program.key(),
+            token_metadata_program: self.token_metadata_program.key(),
+            system_program: self.system_program.key(),
+        }
+        .instruction_with_remaining_accounts(args, &proof_metas(&self.proof));
+
+        let mut account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.tree_config),
+            Some(&self.leaf_owner),
+            Some(&self.leaf_delegate),
+            Some(&self.merkle_tree),
+            Some(&self.payer),
+            Some(&self.tree_creator_or_delegate),
+            Some(&self.collection_authority),
+            self.collection_authority_record.as_ref(),
+            Some(&self.collection_mint),
+            Some(&self.collection_metadata),
+            Some(&self.collection_edition),
+            Some(&self.bubblegum_signer),
+            Some(&self.log_wrapper),
+            Some(&self.compression_program),
+            Some(&self.token_metadata_program),
+            Some(&self.system_program),
+            Some(&self.bubblegum_program),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+        account_infos.extend(self.proof.iter().cloned());
+
+        invoke_instruction(&verify_ix, &account_infos, signer_seeds)
+    }
+}
+
+/// Accounts of `mint_to_collection_v1`, passed the same way as those of
+/// [`VerifyCollectionAccounts`].
+#[derive(Clone)]
+pub struct MintToCollectionAccounts<'info> {
+    pub tree_config: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub leaf_delegate: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub tree_creator_or_delegate: AccountInfo<'info>,
+    pub collection_authority: AccountInfo<'info>,
+    pub collection_authority_record: Option<AccountInfo<'info>>,
+    pub collection_mint: AccountInfo<'info>,
+    pub collection_metadata: AccountInfo<'info>,
+    pub collection_edition: AccountInfo<'info>,
+    pub bubblegum_signer: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub token_metadata_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub bubblegum_program: AccountInfo<'info>,
+}
+
+impl<'info> MintToCollectionAccounts<'info> {
+    /// Mints a new leaf with a verified collection.
+    pub fn invoke(
+        &self,
+        args: MintToCollectionV1InstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let mint_ix = mpl_bubblegum::instructions::MintToCollectionV1 {
+            tree_config: self.tree_config.key(),
+            leaf_owner: self.leaf_owner.key(),
+            leaf_delegate: self.leaf_delegate.key(),
+            merkle_tree: self.merkle_tree.key(),
+            payer: self.payer.key(),
+            tree_creator_or_delegate: self.tree_creator_or_delegate.key(),
+            collection_authority: self.collection_authority.key(),
+            collection_authority_record_pda: self
+                .collection_authority_record
+                .as_ref()
+                .map(|record| record.key()),
+            collection_mint: self.collection_mint.key(),
+            collection_metadata: self.collection_metadata.key(),
+            collection_edition: self.collection_edition.key(),
+            bubblegum_signer: self.bubblegum_signer.key(),
+            log_wrapper: self.log_wrapper.key(),
+            compression_program: self.compression_program.key(),
+            token_metadata_program: self.token_metadata_program.key(),
+            system_program: self.system_program.key(),
+        }
+        .instruction(args);
+
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.tree_config),
+            Some(&self.leaf_owner),
+            Some(&self.leaf_delegate),
+            Some(&self.merkle_tree),
+            Some(&self.payer),
+            Some(&self.tree_creator_or_delegate),
+            Some(&self.collection_authority),
+            self.collection_authority_record.as_ref(),
+            Some(&self.collection_mint),
+            Some(&self.collection_metadata),
+            Some(&self.collection_edition),
+            Some(&self.bubblegum_signer),
+            Some(&self.log_wrapper),
+            Some(&self.compression_program),
+            Some(&self.token_metadata_program),
+            Some(&self.system_program),
+            Some(&self.bubblegum_program),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&mint_ix, &account_infos, signer_seeds)
+    }
+}
+
+pub fn transfer<'info>(
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    accounts: TransferAccounts<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke(
+        TransferInstructionArgs {
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn burn<'info>(
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    accounts: BurnAccounts<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke(
+        BurnInstructionArgs {
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn delegate<'info>(
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    accounts: DelegateAccounts<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke(
+        DelegateInstructionArgs {
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn redeem<'info>(
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    accounts: RedeemAccounts<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke(
+        RedeemInstructionArgs {
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn decompress<'info>(
+    metadata: MetadataArgs,
+    accounts: DecompressAccounts<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke(DecompressV1InstructionArgs { metadata }, signer_seeds)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn verify_collection<'info>(
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    metadata: MetadataArgs,
+    accounts: VerifyCollectionAccounts<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke(
+        VerifyCollectionInstructionArgs {
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            metadata,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn mint_to_collection<'info>(
+    metadata: MetadataArgs,
+    accounts: MintToCollectionAccounts<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke(MintToCollectionV1InstructionArgs { metadata }, signer_seeds)
+}
@@ -1,15 +1,87 @@
-use anchor_lang::prelude::*;
+use {
+    crate::{
+        errors::{from_program_error, UtilErrors},
+        metadata::invoke_instruction,
+        pda::find_collection_authority_record,
+        prelude::*,
+    },
+    mpl_token_metadata::instruction::{approve_collection_authority, revoke_collection_authority},
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn approve<'info>(
-    _collection_mint: AccountInfo<'info>,
-    _collection_metadata: AccountInfo<'info>,
-    _update_authority: AccountInfo<'info>,
-    _new_authority: AccountInfo<'info>,
-    _new_authority_record: AccountInfo<'info>,
-    _system_program: AccountInfo<'info>,
-    _rent_program: AccountInfo<'info>,
-    _payer: AccountInfo<'info>,
-    _signer_seeds: Option<&[&[&[u8]]; 1]>,
+    collection_mint: AccountInfo<'info>,
+    collection_metadata: AccountInfo<'info>,
+    update_authority: AccountInfo<'info>,
+    new_authority: AccountInfo<'info>,
+    new_authority_record: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    return Ok(());
+    let (expected_record, _) =
+        find_collection_authority_record(collection_mint.key, new_authority.key);
+    if expected_record != new_authority_record.key() {
+        return Err(UtilErrors::InvalidNewAuthorityRecord.into());
+    }
+    if !solana_program::system_program::check_id(system_program.key) {
+        msg!("{} is not the system program", system_program.key);
+        return Err(from_program_error(ProgramError::IncorrectProgramId));
+    }
+
+    let approve_ix = approve_collection_authority(
+        mpl_token_metadata::ID,
+        new_authority_record.key(),
+        new_authority.key(),
+        update_authority.key(),
+        payer.key(),
+        collection_metadata.key(),
+        collection_mint.key(),
+    );
+
+    let account_infos = [
+        new_authority_record,
+        new_authority,
+        update_authority,
+        payer,
+        collection_metadata,
+        collection_mint,
+        system_program,
+    ];
+
+    invoke_instruction(&approve_ix, &account_infos, signer_seeds)
+}
+
+pub fn revoke_approval<'info>(
+    collection_mint: AccountInfo<'info>,
+    collection_metadata: AccountInfo<'info>,
+    revoke_authority: AccountInfo<'info>,
+    delegate_authority: AccountInfo<'info>,
+    delegate_authority_record: AccountInfo<'info>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let (expected_record, _) =
+        find_collection_authority_record(collection_mint.key, delegate_authority.key);
+    if expected_record != delegate_authority_record.key() {
+        return Err(UtilErrors::InvalidCollectionAuthorityRecord.into());
+    }
+
+    let revoke_ix = revoke_collection_authority(
+        mpl_token_metadata::ID,
+        delegate_authority_record.key(),
+        delegate_authority.key(),
+        revoke_authority.key(),
+        collection_metadata.key(),
+        collection_mint.key(),
+    );
+
+    let account_infos = [
+        delegate_authority_record,
+        delegate_authority,
+        revoke_authority,
+        collection_metadata,
+        collection_mint,
+    ];
+
+    invoke_instruction(&revoke_ix, &account_infos, signer_seeds)
 }
@@ -0,0 +1,271 @@
+use {
+    crate::{errors::UtilErrors, prelude::*},
+    solana_program::keccak::hashv,
+};
+
+pub mod program {
+    solana_program::declare_id!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+}
+
+pub use program::ID as SPL_ACCOUNT_COMPRESSION_ID;
+
+const CONCURRENT_MERKLE_TREE_ACCOUNT_TYPE: u8 = 1;
+const HEADER_V1: u8 = 0;
+const MAX_BUFFER_SIZE_OFFSET: usize = 2;
+const MAX_DEPTH_OFFSET: usize = 6;
+const AUTHORITY_OFFSET: usize = 10;
+const CREATION_SLOT_OFFSET: usize = 42;
+const TREE_OFFSET: usize = 56;
+const MAX_SUPPORTED_DEPTH: usize = 30;
+
+/// Returns the root of an empty subtree of height `level`.
+pub fn empty_node(level: usize) -> [u8; 32] {
+    (0..level).fold([0u8; 32], |node, _| hashv(&[&node, &node]).0)
+}
+
+/// Borrow-based view over the raw data of an SPL account compression
+/// concurrent merkle tree account.
+#[derive(Clone, Copy)]
+pub struct ConcurrentMerkleTreeReader<'a> {
+    data: &'a [u8],
+    max_depth: usize,
+    max_buffer_size: usize,
+}
+
+impl<'a> ConcurrentMerkleTreeReader<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        if data.len() < TREE_OFFSET
+            || data[0] != CONCURRENT_MERKLE_TREE_ACCOUNT_TYPE
+            || data[1] != HEADER_V1
+        {
+            return Err(UtilErrors::InvalidMerkleTree.into());
+        }
+        let max_buffer_size = u32::from_le_bytes(
+            data[MAX_BUFFER_SIZE_OFFSET..MAX_BUFFER_SIZE_OFFSET + 4]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        let max_depth = u32::from_le_bytes(
+            data[MAX_DEPTH_OFFSET..MAX_DEPTH_OFFSET + 4]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        if max_buffer_size == 0 || max_depth == 0 || max_depth > MAX_SUPPORTED_DEPTH {
+            return Err(UtilErrors::InvalidMerkleTree.into());
+        }
+
+        let reader = Self {
+            data,
+            max_depth,
+            max_buffer_size,
+        };
+        if data.len() < TREE_OFFSET + reader.tree_size()
+            || reader.active_index() >= max_buffer_size
+            || reader.buffer_size() > max_buffer_size
+        {
+            return Err(UtilErrors::InvalidMerkleTree.into());
+        }
+        Ok(reader)
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn max_buffer_size(&self) -> usize {
+        self.max_buffer_size
+    }
+
+    pub fn authority(&self) -> Pubkey {
+        Pubkey::new_from_array(self.array_at(AUTHORITY_OFFSET))
+    }
+
+    pub fn creation_slot(&self) -> u64 {
+        u64::from_le_bytes(self.array_at(CREATION_SLOT_OFFSET))
+    }
+
+    pub fn sequence_number(&self) -> u64 {
+        u64::from_le_bytes(self.array_at(TREE_OFFSET))
+    }
+
+    pub fn active_index(&self) -> usize {
+        u64::from_le_bytes(self.array_at(TREE_OFFSET + 8)) as usize
+    }
+
+    pub fn buffer_size(&self) -> usize {
+        u64::from_le_bytes(self.array_at(TREE_OFFSET + 16)) as usize
+    }
+
+    /// Returns the current root of the tree.
+    pub fn root(&self) -> [u8; 32] {
+        self.changelog_root(self.active_index())
+    }
+
+    /// Index of the rightmost leaf appended to the tree.
+    pub fn rightmost_index(&self) -> u32 {
+        let offset = self.rightmost_proof_offset() + 32 * self.max_depth + 32;
+        u32::from_le_bytes(self.array_at(offset))
+    }
+
+    /// Nodes cached on-chain for the top levels of the tree.
+    pub fn canopy(&self) -> &'a [u8] {
+        &self.data[TREE_OFFSET + self.tree_size()..]
+    }
+
+    /// Checks that `leaf` sits at `index` in the tree, like the account
+    /// compression `verify_leaf` instruction does: `proof` may be truncated
+    /// to the part not covered by the canopy, and may have been computed
+    /// against any `root` still in the changelog buffer as long as the leaf
+    /// itself has not changed since.
+    pub fn prove_leaf(
+        &self,
+        root: [u8; 32],
+        leaf: [u8; 32],
+        proof: &[[u8; 32]],
+        index: u32,
+    ) -> Result<()> {
+        if index as u64 >= 1u64 << self.max_depth || index > self.rightmost_index() {
+            msg!("Leaf index {} is out of bounds", index);
+            return Err(UtilErrors::InvalidMerkleProof.into());
+        }
+
+        let mut full_proof = proof.to_vec();
+        self.fill_in_proof_from_canopy(index, &mut full_proof)?;
+        if full_proof.len() > self.max_depth {
+            return Err(UtilErrors::InvalidMerkleProof.into());
+        }
+        for level in full_proof.len()..self.max_depth {
+            full_proof.push(empty_node(level));
+        }
+
+        let active_index = self.active_index();
+        let (mut changelog_index, use_full_buffer) = match self.find_root_in_changelog(root) {
+            Some(changelog_index) => (changelog_index, false),
+            None => (
+                (active_index + self.max_buffer_size + 1 - self.buffer_size().max(1))
+                    % self.max_buffer_size,
+                true,
+            ),
+        };
+
+        let mut updated_leaf = leaf;
+        loop {
+            if !use_full_buffer && changelog_index == active_index {
+                break;
+            }
+            changelog_index = (changelog_index + 1) % self.max_buffer_size;
+            self.update_proof_or_leaf(changelog_index, index, &mut full_proof, &mut updated_leaf);
+            if use_full_buffer && changelog_index == active_index {
+                break;
+            }
+        }
+        if updated_leaf != leaf {
+            msg!("Leaf {} was modified since the proof was computed", index);
+            return Err(UtilErrors::InvalidMerkleProof.into());
+        }
+
+        let computed_root = full_proof
+            .iter()
+            .enumerate()
+            .fold(leaf, |node, (depth, sibling)| {
+                if (index >> depth) & 1 == 0 {
+                    hashv(&[&node, sibling]).0
+                } else {
+                    hashv(&[sibling, &node]).0
+                }
+            });
+        if computed_root != self.root() {
+            return Err(UtilErrors::InvalidMerkleProof.into());
+        }
+        Ok(())
+    }
+
+    fn find_root_in_changelog(&self, root: [u8; 32]) -> Option<usize> {
+        let active_index = self.active_index();
+        (0..self.buffer_size())
+            .map(|i| (active_index + self.max_buffer_size - i) % self.max_buffer_size)
+            .find(|changelog_index| self.changelog_root(*changelog_index) == root)
+    }
+
+    fn update_proof_or_leaf(
+        &self,
+        changelog_index: usize,
+        leaf_index: u32,
+        proof: &mut [[u8; 32]],
+        leaf: &mut [u8; 32],
+    ) {
+        let offset = self.changelog_offset(changelog_index);
+        let path_offset = offset + 32;
+        let changed_index = u32::from_le_bytes(self.array_at(path_offset + 32 * self.max_depth));
+        if leaf_index != changed_index {
+            let padding = 32 - self.max_depth;
+            let common_path_len =
+                ((leaf_index ^ changed_index) << padding).leading_zeros() as usize;
+            if let Some(critbit_index) = (self.max_depth - 1).checked_sub(common_path_len) {
+                proof[critbit_index] = self.array_at(path_offset + 32 * critbit_index);
+            }
+        } else {
+            *leaf = self.array_at(path_offset);
+        }
+    }
+
+    fn fill_in_proof_from_canopy(&self, index: u32, proof: &mut Vec<[u8; 32]>) -> Result<()> {
+        let canopy = self.canopy();
+        if !canopy.chunks_exact(32).remainder().is_empty() {
+            return Err(UtilErrors::InvalidMerkleTree.into());
+        }
+        let closest_power_of_2 = canopy.len() / 32 + 2;
+        if closest_power_of_2 & (closest_power_of_2 - 1) != 0
+            || closest_power_of_2 > 1 << (self.max_depth + 1)
+        {
+            return Err(UtilErrors::InvalidMerkleTree.into());
+        }
+        let canopy_depth = closest_power_of_2.trailing_zeros() as usize - 1;
+
+        let mut node_index =
+            ((1u64 << self.max_depth) + index as u64) >> (self.max_depth - canopy_depth);
+        let mut inferred_nodes = vec![];
+        while node_index > 1 {
+            // node_index - 2 is the position in the canopy, ^ 1 its sibling
+            let cached_index = (node_index as usize - 2) ^ 1;
+            let cached: [u8; 32] = canopy[cached_index * 32..cached_index * 32 + 32]
+                .try_into()
+                .unwrap();
+            if cached == [0u8; 32] {
+                let level = self.max_depth - (63 - node_index.leading_zeros() as usize);
+                inferred_nodes.push(empty_node(level));
+            } else {
+                inferred_nodes.push(cached);
+            }
+            node_index >>= 1;
+        }
+
+        let overlap = (proof.len() + inferred_nodes.len()).saturating_sub(self.max_depth);
+        proof.extend(inferred_nodes.into_iter().skip(overlap));
+        Ok(())
+    }
+
+    fn changelog_root(&self, changelog_index: usize) -> [u8; 32] {
+        self.array_at(self.changelog_offset(changelog_index))
+    }
+
+    fn changelog_size(&self) -> usize {
+        32 + 32 * self.max_depth + 8
+    }
+
+    fn changelog_offset(&self, changelog_index: usize) -> usize {
+        TREE_OFFSET + 24 + changelog_index * self.changelog_size()
+    }
+
+    fn rightmost_proof_offset(&self) -> usize {
+        TREE_OFFSET + 24 + self.max_buffer_size * self.changelog_size()
+    }
+
+    fn tree_size(&self) -> usize {
+        24 + self.max_buffer_size * self.changelog_size() + 32 * self.max_depth + 40
+    }
+
+    fn array_at<const N: usize>(&self, offset: usize) -> [u8; N] {
+        self.data[offset..offset + N].try_into().unwrap()
+    }
+}
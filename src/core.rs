@@ -0,0 +1,349 @@
+use crate::{
+    errors::UtilErrors, metadata::invoke_instruction, prelude::*, ValidateCollectionCreatorOutput,
+};
+
+pub use mpl_core::{
+    self,
+    accounts::BaseAssetV1,
+    instructions::{
+        AddPluginV1InstructionArgs, BurnV1InstructionArgs, CreateV1InstructionArgs,
+        TransferV1InstructionArgs, UpdatePluginV1InstructionArgs, UpdateV1InstructionArgs,
+    },
+    types::{
+        DataState, FreezeDelegate, Plugin, PluginAuthority, PluginAuthorityPair, UpdateAuthority,
+    },
+    ID as CORE_PROGRAM_ID,
+};
+
+fn optional_key(account: &Option<AccountInfo>) -> Option<Pubkey> {
+    account.as_ref().map(|account| account.key())
+}
+
+fn push_optional<'info>(
+    account_infos: &mut Vec<AccountInfo<'info>>,
+    account: &Option<AccountInfo<'info>>,
+) {
+    if let Some(account) = account {
+        account_infos.push(account.clone());
+    }
+}
+
+#[derive(Clone)]
+pub struct CreateAccounts<'info> {
+    pub asset: AccountInfo<'info>,
+    pub collection: Option<AccountInfo<'info>>,
+    pub authority: Option<AccountInfo<'info>>,
+    pub payer: AccountInfo<'info>,
+    pub owner: Option<AccountInfo<'info>>,
+    pub update_authority: Option<AccountInfo<'info>>,
+    pub system_program: AccountInfo<'info>,
+    pub log_wrapper: Option<AccountInfo<'info>>,
+    /// Core puts its own program id in the place of omitted optional
+    /// accounts, so it is always passed along.
+    pub core_program: AccountInfo<'info>,
+}
+
+impl<'info> CreateAccounts<'info> {
+    pub fn invoke(
+        &self,
+        args: CreateV1InstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let create_ix = mpl_core::instructions::CreateV1 {
+            asset: self.asset.key(),
+            collection: optional_key(&self.collection),
+            authority: optional_key(&self.authority),
+            payer: self.payer.key(),
+            owner: optional_key(&self.owner),
+            update_authority: optional_key(&self.update_authority),
+            system_program: self.system_program.key(),
+            log_wrapper: optional_key(&self.log_wrapper),
+        }
+        .instruction(args);
+
+        let mut account_infos = vec![self.asset.clone()];
+        push_optional(&mut account_infos, &self.collection);
+        push_optional(&mut account_infos, &self.authority);
+        account_infos.push(self.payer.clone());
+        push_optional(&mut account_infos, &self.owner);
+        push_optional(&mut account_infos, &self.update_authority);
+        account_infos.push(self.system_program.clone());
+        push_optional(&mut account_infos, &self.log_wrapper);
+        account_infos.push(self.core_program.clone());
+
+        invoke_instruction(&create_ix, &account_infos, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct TransferAccounts<'info> {
+    pub asset: AccountInfo<'info>,
+    pub collection: Option<AccountInfo<'info>>,
+    pub payer: AccountInfo<'info>,
+    pub authority: Option<AccountInfo<'info>>,
+    pub new_owner: AccountInfo<'info>,
+    pub system_program: Option<AccountInfo<'info>>,
+    pub log_wrapper: Option<AccountInfo<'info>>,
+    pub core_program: AccountInfo<'info>,
+}
+
+impl<'info> TransferAccounts<'info> {
+    pub fn invoke(
+        &self,
+        args: TransferV1InstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let transfer_ix = mpl_core::instructions::TransferV1 {
+            asset: self.asset.key(),
+            collection: optional_key(&self.collection),
+            payer: self.payer.key(),
+            authority: optional_key(&self.authority),
+            new_owner: self.new_owner.key(),
+            system_program: optional_key(&self.system_program),
+            log_wrapper: optional_key(&self.log_wrapper),
+        }
+        .instruction(args);
+
+        let mut account_infos = vec![self.asset.clone()];
+        push_optional(&mut account_infos, &self.collection);
+        account_infos.push(self.payer.clone());
+        push_optional(&mut account_infos, &self.authority);
+        account_infos.push(self.new_owner.clone());
+        push_optional(&mut account_infos, &self.system_program);
+        push_optional(&mut account_infos, &self.log_wrapper);
+        account_infos.push(self.core_program.clone());
+
+        invoke_instruction(&transfer_ix, &account_infos, signer_seeds)
+    }
+}
+
+/// Accounts shared by the Core instructions acting on an existing asset.
+#[derive(Clone)]
+pub struct AssetAccounts<'info> {
+    pub asset: AccountInfo<'info>,
+    pub collection: Option<AccountInfo<'info>>,
+    pub payer: AccountInfo<'info>,
+    pub authority: Option<AccountInfo<'info>>,
+    pub system_program: AccountInfo<'info>,
+    pub log_wrapper: Option<AccountInfo<'info>>,
+    pub core_program: AccountInfo<'info>,
+}
+
+impl<'info> AssetAccounts<'info> {
+    fn account_infos(&self) -> Vec<AccountInfo<'info>> {
+        let mut account_infos = vec![self.asset.clone()];
+        push_optional(&mut account_infos, &self.collection);
+        account_infos.push(self.payer.clone());
+        push_optional(&mut account_infos, &self.authority);
+        account_infos.push(self.system_program.clone());
+        push_optional(&mut account_infos, &self.log_wrapper);
+        account_infos.push(self.core_program.clone());
+        account_infos
+    }
+
+    pub fn invoke_burn(
+        &self,
+        args: BurnV1InstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let burn_ix = mpl_core::instructions::BurnV1 {
+            asset: self.asset.key(),
+            collection: optional_key(&self.collection),
+            payer: self.payer.key(),
+            authority: optional_key(&self.authority),
+            system_program: Some(self.system_program.key()),
+            log_wrapper: optional_key(&self.log_wrapper),
+        }
+        .instruction(args);
+
+        invoke_instruction(&burn_ix, &self.account_infos(), signer_seeds)
+    }
+
+    pub fn invoke_update(
+        &self,
+        args: UpdateV1InstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let update_ix = mpl_core::instructions::UpdateV1 {
+            asset: self.asset.key(),
+            collection: optional_key(&self.collection),
+            payer: self.payer.key(),
+            authority: optional_key(&self.authority),
+            system_program: self.system_program.key(),
+            log_wrapper: optional_key(&self.log_wrapper),
+        }
+        .instruction(args);
+
+        invoke_instruction(&update_ix, &self.account_infos(), signer_seeds)
+    }
+
+    pub fn invoke_add_plugin(
+        &self,
+        args: AddPluginV1InstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let add_plugin_ix = mpl_core::instructions::AddPluginV1 {
+            asset: self.asset.key(),
+            collection: optional_key(&self.collection),
+            payer: self.payer.key(),
+            authority: optional_key(&self.authority),
+            system_program: self.system_program.key(),
+            log_wrapper: optional_key(&self.log_wrapper),
+        }
+        .instruction(args);
+
+        invoke_instruction(&add_plugin_ix, &self.account_infos(), signer_seeds)
+    }
+
+    pub fn invoke_update_plugin(
+        &self,
+        args: UpdatePluginV1InstructionArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let update_plugin_ix = mpl_core::instructions::UpdatePluginV1 {
+            asset: self.asset.key(),
+            collection: optional_key(&self.collection),
+            payer: self.payer.key(),
+            authority: optional_key(&self.authority),
+            system_program: self.system_program.key(),
+            log_wrapper: optional_key(&self.log_wrapper),
+        }
+        .instruction(args);
+
+        invoke_instruction(&update_plugin_ix, &self.account_infos(), signer_seeds)
+    }
+}
+
+pub fn create(
+    name: String,
+    uri: String,
+    plugins: Option<Vec<PluginAuthorityPair>>,
+    accounts: CreateAccounts,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke(
+        CreateV1InstructionArgs {
+            data_state: DataState::AccountState,
+            name,
+            uri,
+            plugins,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn transfer(accounts: TransferAccounts, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+    accounts.invoke(
+        TransferV1InstructionArgs {
+            compression_proof: None,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn burn(accounts: AssetAccounts, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+    accounts.invoke_burn(
+        BurnV1InstructionArgs {
+            compression_proof: None,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn update(
+    new_name: Option<String>,
+    new_uri: Option<String>,
+    new_update_authority: Option<UpdateAuthority>,
+    accounts: AssetAccounts,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke_update(
+        UpdateV1InstructionArgs {
+            new_name,
+            new_uri,
+            new_update_authority,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn add_plugin(
+    plugin: Plugin,
+    init_authority: Option<PluginAuthority>,
+    accounts: AssetAccounts,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke_add_plugin(
+        AddPluginV1InstructionArgs {
+            plugin,
+            init_authority,
+        },
+        signer_seeds,
+    )
+}
+
+pub fn update_plugin(
+    plugin: Plugin,
+    accounts: AssetAccounts,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    accounts.invoke_update_plugin(UpdatePluginV1InstructionArgs { plugin }, signer_seeds)
+}
+
+/// Adds a freeze delegate plugin with `delegate` as its authority, which can
+/// then `freeze` and `thaw` the asset.
+pub fn approve_freeze_delegate(
+    delegate: Pubkey,
+    accounts: AssetAccounts,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    add_plugin(
+        Plugin::FreezeDelegate(FreezeDelegate { frozen: false }),
+        Some(PluginAuthority::Address { address: delegate }),
+        accounts,
+        signer_seeds,
+    )
+}
+
+pub fn freeze(accounts: AssetAccounts, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+    update_plugin(
+        Plugin::FreezeDelegate(FreezeDelegate { frozen: true }),
+        accounts,
+        signer_seeds,
+    )
+}
+
+pub fn thaw(accounts: AssetAccounts, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+    update_plugin(
+        Plugin::FreezeDelegate(FreezeDelegate { frozen: false }),
+        accounts,
+        signer_seeds,
+    )
+}
+
+/// Reads a Core asset, checking it is owned by the Core program.
+pub fn read_asset(asset: &AccountInfo) -> Result<BaseAssetV1> {
+    if *asset.owner != CORE_PROGRAM_ID {
+        return Err(UtilErrors::InvalidCoreAsset.into());
+    }
+    let data = asset.try_borrow_data()?;
+    match BaseAssetV1::from_bytes(&data) {
+        Ok(base_asset) if base_asset.key == mpl_core::types::Key::AssetV1 => Ok(base_asset),
+        _ => Err(UtilErrors::InvalidCoreAsset.into()),
+    }
+}
+
+/// Core counterpart of `validate_collection_creator`. Core assets belong to
+/// a collection through their update authority, which the collection has to
+/// approve, so membership needs no separate verification.
+pub fn validate_collection(
+    asset: &AccountInfo,
+    collections: &[Pubkey],
+) -> Result<ValidateCollectionCreatorOutput> {
+    match read_asset(asset)?.update_authority {
+        UpdateAuthority::Collection(address) if collections.contains(&address) => {
+            Ok(ValidateCollectionCreatorOutput::Collection { address })
+        }
+        _ => Err(UtilErrors::InvalidNFT.into()),
+    }
+}
@@ -1,13 +1,90 @@
-use anchor_lang::prelude::error_code;
+/// Declares `UtilErrors` as an Anchor `#[error_code]` enum, or as a plain
+/// enum converting into `ProgramError` for native programs. Both number the
+/// variants from 6000 on, so the codes are the same either way.
+macro_rules! util_errors {
+    ($($variant:ident => $msg:literal,)*) => {
+        #[cfg(feature = "anchor")]
+        #[anchor_lang::prelude::error_code]
+        pub enum UtilErrors {
+            $(#[msg($msg)] $variant,)*
+        }
 
-#[error_code]
-pub enum UtilErrors {
-    #[msg("Opertaion overflowed")]
-    Overflow,
+        #[cfg(not(feature = "anchor"))]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(u32)]
+        pub enum UtilErrors {
+            $($variant,)*
+        }
 
-    #[msg("NFT validation failed")]
-    InvalidNFT,
+        #[cfg(not(feature = "anchor"))]
+        impl UtilErrors {
+            pub fn message(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $msg,)*
+                }
+            }
+        }
+    };
+}
+
+util_errors! {
+    Overflow => "Opertaion overflowed",
+    InvalidNFT => "NFT validation failed",
+    InvalidNewAuthorityRecord => "Invalid New Authority Record",
+    MissingCreateAccount => "Missing required account for metadata create",
+    MissingMintAccount => "Missing required account for metadata mint",
+    MissingBurnAccount => "Missing required account for metadata burn",
+    MissingUpdateAccount => "Missing required account for metadata update",
+    MissingTransferAccount => "Missing required account for metadata transfer",
+    MissingLockAccount => "Missing required account for metadata lock",
+    MissingUnlockAccount => "Missing required account for metadata unlock",
+    MissingDelegateAccount => "Missing required account for metadata delegate",
+    MissingRevokeAccount => "Missing required account for metadata revoke",
+    MissingVerifyAccount => "Missing required account for metadata verify",
+    InvalidPda => "Account does not match its Token Metadata PDA",
+    MissingEdition => "Edition account is required for this token standard",
+    MissingTokenRecord => "Token record account is required for this token standard",
+    MissingDelegateRecord => "Delegate record account is required for this delegate role",
+    InvalidMetadata => "Metadata account data is malformed",
+    InvalidMerkleTree => "Account is not a valid concurrent merkle tree",
+    InvalidMerkleProof => "Leaf could not be proved against the merkle tree",
+    InvalidTokenProgram => "Token program does not own the mint",
+    InvalidCoreAsset => "Account is not a Metaplex Core asset",
+    InvalidCollectionAuthorityRecord => "Account is not the collection authority record of the delegate",
+    UnsupportedGroupMemberPointer => "Token group membership is only read from the mint itself",
+}
+
+/// Offset Anchor adds to `#[error_code]` variants.
+#[cfg(not(feature = "anchor"))]
+const ERROR_CODE_OFFSET: u32 = 6000;
+
+#[cfg(not(feature = "anchor"))]
+impl std::fmt::Display for UtilErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+#[cfg(not(feature = "anchor"))]
+impl From<UtilErrors> for solana_program::program_error::ProgramError {
+    fn from(error: UtilErrors) -> Self {
+        solana_program::msg!("Error: {}", error);
+        Self::Custom(ERROR_CODE_OFFSET + error as u32)
+    }
+}
+
+/// Converts the `ProgramError` of a raw `invoke` into the crate's `Error`,
+/// which it already is in native builds.
+#[cfg(feature = "anchor")]
+pub(crate) fn from_program_error(
+    error: solana_program::program_error::ProgramError,
+) -> crate::prelude::Error {
+    error.into()
+}
 
-    #[msg("Invalid New Authority Record")]
-    InvalidNewAuthorityRecord,
+#[cfg(not(feature = "anchor"))]
+pub(crate) fn from_program_error(
+    error: solana_program::program_error::ProgramError,
+) -> crate::prelude::Error {
+    error
 }
@@ -0,0 +1,321 @@
+//! Owned, in-memory accounts for host-side tests.
+//!
+//! A [`TestAccount`] lays its fields out the way the runtime serializes an
+//! account into a program's input, including the spare room after the data,
+//! so the `AccountInfo` it hands out supports `realloc` like a real one.
+
+use {
+    crate::{
+        pda::{find_edition, find_metadata},
+        prelude::*,
+    },
+    mpl_token_metadata::{
+        state::{
+            Creator, Data, Key as MetadataKey, Metadata, TokenStandard, MAX_MASTER_EDITION_LEN,
+            MAX_METADATA_LEN,
+        },
+        utils::puff_out_data_fields,
+    },
+    solana_program::{
+        bpf_loader, clock::Epoch, entrypoint::MAX_PERMITTED_DATA_INCREASE, program_option::COption,
+        program_pack::Pack, system_program, sysvar,
+    },
+    spl_token::state::{Account as TokenAccount, AccountState, Mint},
+};
+
+const ORIGINAL_DATA_LEN_OFFSET: usize = 4;
+const KEY_OFFSET: usize = 8;
+const OWNER_OFFSET: usize = 40;
+const LAMPORTS_OFFSET: usize = 72;
+const DATA_LEN_OFFSET: usize = 80;
+const DATA_OFFSET: usize = 88;
+
+/// Builds a [`TestAccount`]. Accounts default to a unique key, owned by the
+/// system program, with no lamports, no data and neither flag set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountBuilder {
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    is_signer: bool,
+    is_writable: bool,
+    executable: bool,
+    rent_epoch: Epoch,
+}
+
+impl AccountBuilder {
+    pub fn new() -> Self {
+        Self {
+            key: Pubkey::new_unique(),
+            owner: system_program::ID,
+            lamports: 0,
+            data: vec![],
+            is_signer: false,
+            is_writable: false,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    pub fn key(mut self, key: Pubkey) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn owner(mut self, owner: Pubkey) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    pub fn lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// Sets the lamports to the rent-exempt minimum of the current data.
+    pub fn rent_exempt(self) -> Self {
+        let lamports = Rent::default().minimum_balance(self.data.len());
+        self.lamports(lamports)
+    }
+
+    pub fn data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+
+    pub fn signer(mut self) -> Self {
+        self.is_signer = true;
+        self
+    }
+
+    pub fn writable(mut self) -> Self {
+        self.is_writable = true;
+        self
+    }
+
+    pub fn executable(mut self) -> Self {
+        self.executable = true;
+        self
+    }
+
+    pub fn rent_epoch(mut self, rent_epoch: Epoch) -> Self {
+        self.rent_epoch = rent_epoch;
+        self
+    }
+
+    pub fn build(self) -> TestAccount {
+        let data_len = self.data.len();
+        let len = DATA_OFFSET + data_len + MAX_PERMITTED_DATA_INCREASE;
+        let mut account = TestAccount {
+            buffer: vec![0; len.div_ceil(8)],
+            is_signer: self.is_signer,
+            is_writable: self.is_writable,
+            executable: self.executable,
+            rent_epoch: self.rent_epoch,
+        };
+
+        let bytes = account.bytes_mut();
+        bytes[ORIGINAL_DATA_LEN_OFFSET..KEY_OFFSET]
+            .copy_from_slice(&(data_len as u32).to_le_bytes());
+        bytes[KEY_OFFSET..OWNER_OFFSET].copy_from_slice(self.key.as_ref());
+        bytes[OWNER_OFFSET..LAMPORTS_OFFSET].copy_from_slice(self.owner.as_ref());
+        bytes[LAMPORTS_OFFSET..DATA_LEN_OFFSET].copy_from_slice(&self.lamports.to_le_bytes());
+        bytes[DATA_LEN_OFFSET..DATA_OFFSET].copy_from_slice(&(data_len as u64).to_le_bytes());
+        bytes[DATA_OFFSET..DATA_OFFSET + data_len].copy_from_slice(&self.data);
+        account
+    }
+}
+
+impl Default for AccountBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An account owning its key, owner, lamports and data.
+///
+/// Changes made through [`TestAccount::info`], including reallocations, stay
+/// visible on the account once the `AccountInfo` is dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestAccount {
+    /// Kept as `u64`s so the lamports are aligned.
+    buffer: Vec<u64>,
+    is_signer: bool,
+    is_writable: bool,
+    executable: bool,
+    rent_epoch: Epoch,
+}
+
+impl TestAccount {
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: any `u64` buffer is a valid byte buffer eight times as long.
+        unsafe { std::slice::from_raw_parts(self.buffer.as_ptr().cast(), self.buffer.len() * 8) }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as in `bytes`.
+        unsafe {
+            std::slice::from_raw_parts_mut(self.buffer.as_mut_ptr().cast(), self.buffer.len() * 8)
+        }
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(self.bytes()[offset..offset + 8].try_into().unwrap())
+    }
+
+    pub fn key(&self) -> Pubkey {
+        Pubkey::try_from(&self.bytes()[KEY_OFFSET..OWNER_OFFSET]).unwrap()
+    }
+
+    pub fn owner(&self) -> Pubkey {
+        Pubkey::try_from(&self.bytes()[OWNER_OFFSET..LAMPORTS_OFFSET]).unwrap()
+    }
+
+    pub fn lamports(&self) -> u64 {
+        self.read_u64(LAMPORTS_OFFSET)
+    }
+
+    pub fn data(&self) -> &[u8] {
+        let data_len = self.read_u64(DATA_LEN_OFFSET) as usize;
+        &self.bytes()[DATA_OFFSET..DATA_OFFSET + data_len]
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        let data_len = self.read_u64(DATA_LEN_OFFSET) as usize;
+        let bytes = self.buffer.as_mut_ptr().cast::<u8>();
+        // SAFETY: the key, owner, lamports and data regions are disjoint and
+        // in bounds, the lamports are 8-byte aligned, and `self` stays
+        // mutably borrowed for as long as the account info lives.
+        unsafe {
+            AccountInfo::new(
+                &*bytes.add(KEY_OFFSET).cast::<Pubkey>(),
+                self.is_signer,
+                self.is_writable,
+                &mut *bytes.add(LAMPORTS_OFFSET).cast::<u64>(),
+                std::slice::from_raw_parts_mut(bytes.add(DATA_OFFSET), data_len),
+                &*bytes.add(OWNER_OFFSET).cast::<Pubkey>(),
+                self.executable,
+                self.rent_epoch,
+            )
+        }
+    }
+}
+
+/// An executable program account.
+pub fn program(program_id: Pubkey) -> AccountBuilder {
+    AccountBuilder::new()
+        .key(program_id)
+        .owner(bpf_loader::ID)
+        .executable()
+}
+
+/// The rent sysvar holding `Rent::default()`.
+pub fn rent_sysvar() -> AccountBuilder {
+    let rent = Rent::default();
+    // Bincode layout of `Rent`.
+    let mut data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+    data.extend(rent.exemption_threshold.to_le_bytes());
+    data.push(rent.burn_percent);
+    AccountBuilder::new()
+        .key(sysvar::rent::ID)
+        .owner(sysvar::ID)
+        .data(data)
+        .rent_exempt()
+}
+
+/// An initialized SPL Token mint. Use `owner` to make it a Token-2022 one.
+pub fn mint(mint_authority: Option<Pubkey>, supply: u64, decimals: u8) -> AccountBuilder {
+    let mut data = vec![0; Mint::LEN];
+    Mint {
+        mint_authority: mint_authority.into(),
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: mint_authority.into(),
+    }
+    .pack_into_slice(&mut data);
+    AccountBuilder::new()
+        .owner(spl_token::ID)
+        .data(data)
+        .rent_exempt()
+}
+
+/// An initialized SPL Token account of `owner` holding `amount` of `mint`.
+pub fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> AccountBuilder {
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    AccountBuilder::new()
+        .owner(spl_token::ID)
+        .data(data)
+        .rent_exempt()
+}
+
+/// Non-fungible metadata of `mint`, with `update_authority` as its only,
+/// verified creator. Adjust the fields before passing it to [`metadata`].
+pub fn nft_metadata(mint: Pubkey, update_authority: Pubkey) -> Metadata {
+    Metadata {
+        key: MetadataKey::MetadataV1,
+        update_authority,
+        mint,
+        data: Data {
+            name: "Test NFT".to_string(),
+            symbol: "TEST".to_string(),
+            uri: "https://example.com/nft.json".to_string(),
+            seller_fee_basis_points: 500,
+            creators: Some(vec![Creator {
+                address: update_authority,
+                verified: true,
+                share: 100,
+            }]),
+        },
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: Some(find_edition(&mint).1),
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: None,
+        uses: None,
+        collection_details: None,
+        programmable_config: None,
+    }
+}
+
+/// The Token Metadata account of `metadata.mint`, padded the way Token
+/// Metadata stores it.
+pub fn metadata(metadata: &Metadata) -> AccountBuilder {
+    let mut metadata = metadata.clone();
+    puff_out_data_fields(&mut metadata);
+    let mut data = vec![0; MAX_METADATA_LEN];
+    metadata.save(&mut data).unwrap();
+    AccountBuilder::new()
+        .key(find_metadata(&metadata.mint).0)
+        .owner(mpl_token_metadata::ID)
+        .data(data)
+        .rent_exempt()
+}
+
+/// The master edition of `mint`.
+pub fn master_edition(mint: Pubkey, supply: u64, max_supply: Option<u64>) -> AccountBuilder {
+    let mut data = vec![0; MAX_MASTER_EDITION_LEN];
+    data[0] = MetadataKey::MasterEditionV2 as u8;
+    data[1..9].copy_from_slice(&supply.to_le_bytes());
+    if let Some(max_supply) = max_supply {
+        data[9] = 1;
+        data[10..18].copy_from_slice(&max_supply.to_le_bytes());
+    }
+    AccountBuilder::new()
+        .key(find_edition(&mint).0)
+        .owner(mpl_token_metadata::ID)
+        .data(data)
+        .rent_exempt()
+}
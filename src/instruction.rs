@@ -0,0 +1,574 @@
+//! Token Metadata instructions built from pubkeys alone.
+//!
+//! The account wrappers in [`crate::metadata`] build their instructions here,
+//! so host-side clients get the exact same account ordering through the
+//! `client` feature.
+
+use {
+    crate::{errors::UtilErrors, prelude::*},
+    mpl_token_metadata::instruction::{
+        builders::{
+            BurnBuilder, CreateBuilder, DelegateBuilder, LockBuilder, MintBuilder, RevokeBuilder,
+            TransferBuilder, UnlockBuilder, UpdateBuilder, VerifyBuilder,
+        },
+        BurnArgs, CreateArgs, DelegateArgs, InstructionBuilder, LockArgs, MintArgs, RevokeArgs,
+        TransferArgs, UnlockArgs, UpdateArgs, VerificationArgs,
+    },
+    solana_program::instruction::Instruction,
+};
+
+fn builder_error(err: Box<dyn std::error::Error>, error: UtilErrors) -> Error {
+    msg!("{}", err);
+    error.into()
+}
+
+/// Pairs `instruction` with the accounts a program needs to invoke it through
+/// CPI, Token Metadata itself included.
+#[cfg(feature = "client")]
+fn with_cpi_accounts(instruction: Instruction) -> (Instruction, Vec<AccountMeta>) {
+    let mut accounts = instruction.accounts.clone();
+    accounts.push(AccountMeta::new_readonly(instruction.program_id, false));
+    (instruction, accounts)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreateKeys {
+    pub metadata: Pubkey,
+    pub master_edition: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub update_authority: Pubkey,
+    pub system_program: Pubkey,
+    pub sysvar_instructions: Pubkey,
+    pub spl_token_program: Pubkey,
+    pub initialize_mint: bool,
+    pub update_authority_as_signer: bool,
+}
+
+impl CreateKeys {
+    pub fn build(&self, args: CreateArgs) -> Result<Instruction> {
+        let mut builder = CreateBuilder::new();
+        builder
+            .metadata(self.metadata)
+            .mint(self.mint)
+            .authority(self.authority)
+            .payer(self.payer)
+            .update_authority(self.update_authority)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions)
+            .spl_token_program(self.spl_token_program)
+            .initialize_mint(self.initialize_mint)
+            .update_authority_as_signer(self.update_authority_as_signer);
+
+        if let Some(master_edition) = self.master_edition {
+            builder.master_edition(master_edition);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingCreateAccount))?
+            .instruction())
+    }
+
+    #[cfg(feature = "client")]
+    pub fn instruction(&self, args: CreateArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build(args).map(with_cpi_accounts)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MintKeys {
+    pub token: Pubkey,
+    pub token_owner: Option<Pubkey>,
+    pub metadata: Pubkey,
+    pub master_edition: Option<Pubkey>,
+    pub token_record: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub delegate_record: Option<Pubkey>,
+    pub payer: Pubkey,
+    pub system_program: Pubkey,
+    pub sysvar_instructions: Pubkey,
+    pub spl_token_program: Pubkey,
+    pub spl_ata_program: Pubkey,
+    pub authorization_rules_program: Option<Pubkey>,
+    pub authorization_rules: Option<Pubkey>,
+}
+
+impl MintKeys {
+    pub fn build(&self, args: MintArgs) -> Result<Instruction> {
+        let mut builder = MintBuilder::new();
+        builder
+            .token(self.token)
+            .metadata(self.metadata)
+            .mint(self.mint)
+            .authority(self.authority)
+            .payer(self.payer)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions)
+            .spl_token_program(self.spl_token_program)
+            .spl_ata_program(self.spl_ata_program);
+
+        if let Some(token_owner) = self.token_owner {
+            builder.token_owner(token_owner);
+        }
+        if let Some(master_edition) = self.master_edition {
+            builder.master_edition(master_edition);
+        }
+        if let Some(token_record) = self.token_record {
+            builder.token_record(token_record);
+        }
+        if let Some(delegate_record) = self.delegate_record {
+            builder.delegate_record(delegate_record);
+        }
+        if let Some(authorization_rules_program) = self.authorization_rules_program {
+            builder.authorization_rules_program(authorization_rules_program);
+        }
+        if let Some(authorization_rules) = self.authorization_rules {
+            builder.authorization_rules(authorization_rules);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingMintAccount))?
+            .instruction())
+    }
+
+    #[cfg(feature = "client")]
+    pub fn instruction(&self, args: MintArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build(args).map(with_cpi_accounts)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BurnKeys {
+    pub authority: Pubkey,
+    pub collection_metadata: Option<Pubkey>,
+    pub metadata: Pubkey,
+    pub edition: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub token: Pubkey,
+    pub master_edition: Option<Pubkey>,
+    pub master_edition_mint: Option<Pubkey>,
+    pub master_edition_token: Option<Pubkey>,
+    pub edition_marker: Option<Pubkey>,
+    pub token_record: Option<Pubkey>,
+    pub system_program: Pubkey,
+    pub sysvar_instructions: Pubkey,
+    pub spl_token_program: Pubkey,
+}
+
+impl BurnKeys {
+    pub fn build(&self, args: BurnArgs) -> Result<Instruction> {
+        let mut builder = BurnBuilder::new();
+        builder
+            .authority(self.authority)
+            .metadata(self.metadata)
+            .mint(self.mint)
+            .token(self.token)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions)
+            .spl_token_program(self.spl_token_program);
+
+        if let Some(collection_metadata) = self.collection_metadata {
+            builder.collection_metadata(collection_metadata);
+        }
+        if let Some(edition) = self.edition {
+            builder.edition(edition);
+        }
+        if let Some(master_edition) = self.master_edition {
+            builder.master_edition(master_edition);
+        }
+        if let Some(master_edition_mint) = self.master_edition_mint {
+            builder.master_edition_mint(master_edition_mint);
+        }
+        if let Some(master_edition_token) = self.master_edition_token {
+            builder.master_edition_token(master_edition_token);
+        }
+        if let Some(edition_marker) = self.edition_marker {
+            builder.edition_marker(edition_marker);
+        }
+        if let Some(token_record) = self.token_record {
+            builder.token_record(token_record);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingBurnAccount))?
+            .instruction())
+    }
+
+    #[cfg(feature = "client")]
+    pub fn instruction(&self, args: BurnArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build(args).map(with_cpi_accounts)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UpdateKeys {
+    pub delegate_record: Option<Pubkey>,
+    pub token: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub metadata: Pubkey,
+    pub edition: Option<Pubkey>,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub system_program: Pubkey,
+    pub sysvar_instructions: Pubkey,
+    pub authorization_rules_program: Option<Pubkey>,
+    pub authorization_rules: Option<Pubkey>,
+}
+
+impl UpdateKeys {
+    pub fn build(&self, args: UpdateArgs) -> Result<Instruction> {
+        let mut builder = UpdateBuilder::new();
+        builder
+            .authority(self.authority)
+            .mint(self.mint)
+            .metadata(self.metadata)
+            .payer(self.payer)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions);
+
+        if let Some(delegate_record) = self.delegate_record {
+            builder.delegate_record(delegate_record);
+        }
+        if let Some(token) = self.token {
+            builder.token(token);
+        }
+        if let Some(edition) = self.edition {
+            builder.edition(edition);
+        }
+        if let Some(authorization_rules_program) = self.authorization_rules_program {
+            builder.authorization_rules_program(authorization_rules_program);
+        }
+        if let Some(authorization_rules) = self.authorization_rules {
+            builder.authorization_rules(authorization_rules);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingUpdateAccount))?
+            .instruction())
+    }
+
+    #[cfg(feature = "client")]
+    pub fn instruction(&self, args: UpdateArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build(args).map(with_cpi_accounts)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferKeys {
+    pub source_token_account: Pubkey,
+    pub source_token_account_owner: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub destination_token_account_owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_metadata: Pubkey,
+    pub token_edition: Option<Pubkey>,
+    pub source_token_account_record: Option<Pubkey>,
+    pub destination_token_account_record: Option<Pubkey>,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub associated_token_program: Pubkey,
+    pub sysvar_instructions: Pubkey,
+    pub authorization_rules_program: Option<Pubkey>,
+    pub authorization_rules: Option<Pubkey>,
+}
+
+impl TransferKeys {
+    pub fn build(&self, args: TransferArgs) -> Result<Instruction> {
+        let mut builder = TransferBuilder::new();
+        builder
+            .token(self.source_token_account)
+            .token_owner(self.source_token_account_owner)
+            .destination(self.destination_token_account)
+            .destination_owner(self.destination_token_account_owner)
+            .mint(self.token_mint)
+            .metadata(self.token_metadata)
+            .authority(self.authority)
+            .payer(self.payer)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions)
+            .spl_token_program(self.token_program)
+            .spl_ata_program(self.associated_token_program);
+
+        if let Some(token_edition) = self.token_edition {
+            builder.edition(token_edition);
+        }
+        if let Some(source_token_account_record) = self.source_token_account_record {
+            builder.owner_token_record(source_token_account_record);
+        }
+        if let Some(destination_token_account_record) = self.destination_token_account_record {
+            builder.destination_token_record(destination_token_account_record);
+        }
+        if let Some(authorization_rules_program) = self.authorization_rules_program {
+            builder.authorization_rules_program(authorization_rules_program);
+        }
+        if let Some(authorization_rules) = self.authorization_rules {
+            builder.authorization_rules(authorization_rules);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingTransferAccount))?
+            .instruction())
+    }
+
+    #[cfg(feature = "client")]
+    pub fn instruction(&self, args: TransferArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build(args).map(with_cpi_accounts)
+    }
+}
+
+/// Accounts of both `Lock` and `Unlock`, which take the same ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockKeys {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_account: Pubkey,
+    pub token_account_owner: Option<Pubkey>,
+    pub token_metadata: Pubkey,
+    pub token_edition: Option<Pubkey>,
+    pub token_record: Option<Pubkey>,
+    pub payer: Pubkey,
+    pub system_program: Pubkey,
+    pub sysvar_instructions: Pubkey,
+    pub token_program: Pubkey,
+    pub authorization_rules_program: Option<Pubkey>,
+    pub authorization_rules: Option<Pubkey>,
+}
+
+impl LockKeys {
+    pub fn build_lock(&self, args: LockArgs) -> Result<Instruction> {
+        let mut builder = LockBuilder::new();
+        builder
+            .authority(self.authority)
+            .token(self.token_account)
+            .mint(self.token_mint)
+            .metadata(self.token_metadata)
+            .payer(self.payer)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions)
+            .spl_token_program(self.token_program);
+
+        if let Some(token_account_owner) = self.token_account_owner {
+            builder.token_owner(token_account_owner);
+        }
+        if let Some(token_edition) = self.token_edition {
+            builder.edition(token_edition);
+        }
+        if let Some(token_record) = self.token_record {
+            builder.token_record(token_record);
+        }
+        if let Some(authorization_rules_program) = self.authorization_rules_program {
+            builder.authorization_rules_program(authorization_rules_program);
+        }
+        if let Some(authorization_rules) = self.authorization_rules {
+            builder.authorization_rules(authorization_rules);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingLockAccount))?
+            .instruction())
+    }
+
+    pub fn build_unlock(&self, args: UnlockArgs) -> Result<Instruction> {
+        let mut builder = UnlockBuilder::new();
+        builder
+            .authority(self.authority)
+            .token(self.token_account)
+            .mint(self.token_mint)
+            .metadata(self.token_metadata)
+            .payer(self.payer)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions)
+            .spl_token_program(self.token_program);
+
+        if let Some(token_account_owner) = self.token_account_owner {
+            builder.token_owner(token_account_owner);
+        }
+        if let Some(token_edition) = self.token_edition {
+            builder.edition(token_edition);
+        }
+        if let Some(token_record) = self.token_record {
+            builder.token_record(token_record);
+        }
+        if let Some(authorization_rules_program) = self.authorization_rules_program {
+            builder.authorization_rules_program(authorization_rules_program);
+        }
+        if let Some(authorization_rules) = self.authorization_rules {
+            builder.authorization_rules(authorization_rules);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingUnlockAccount))?
+            .instruction())
+    }
+
+    #[cfg(feature = "client")]
+    pub fn lock_instruction(&self, args: LockArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build_lock(args).map(with_cpi_accounts)
+    }
+
+    #[cfg(feature = "client")]
+    pub fn unlock_instruction(&self, args: UnlockArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build_unlock(args).map(with_cpi_accounts)
+    }
+}
+
+/// Accounts of both `Delegate` and `Revoke`, which take the same ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DelegateKeys {
+    pub delegate_record: Option<Pubkey>,
+    pub delegate: Pubkey,
+    pub metadata: Pubkey,
+    pub master_edition: Option<Pubkey>,
+    pub token_record: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub system_program: Pubkey,
+    pub sysvar_instructions: Pubkey,
+    pub token_program: Pubkey,
+    pub authorization_rules_program: Option<Pubkey>,
+    pub authorization_rules: Option<Pubkey>,
+}
+
+impl DelegateKeys {
+    pub fn build_delegate(&self, args: DelegateArgs) -> Result<Instruction> {
+        let mut builder = DelegateBuilder::new();
+        builder
+            .delegate(self.delegate)
+            .metadata(self.metadata)
+            .mint(self.mint)
+            .token(self.token_account)
+            .authority(self.authority)
+            .payer(self.payer)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions)
+            .spl_token_program(self.token_program);
+
+        if let Some(delegate_record) = self.delegate_record {
+            builder.delegate_record(delegate_record);
+        }
+        if let Some(master_edition) = self.master_edition {
+            builder.master_edition(master_edition);
+        }
+        if let Some(token_record) = self.token_record {
+            builder.token_record(token_record);
+        }
+        if let Some(authorization_rules_program) = self.authorization_rules_program {
+            builder.authorization_rules_program(authorization_rules_program);
+        }
+        if let Some(authorization_rules) = self.authorization_rules {
+            builder.authorization_rules(authorization_rules);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingDelegateAccount))?
+            .instruction())
+    }
+
+    pub fn build_revoke(&self, args: RevokeArgs) -> Result<Instruction> {
+        let mut builder = RevokeBuilder::new();
+        builder
+            .delegate(self.delegate)
+            .metadata(self.metadata)
+            .mint(self.mint)
+            .token(self.token_account)
+            .authority(self.authority)
+            .payer(self.payer)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions)
+            .spl_token_program(self.token_program);
+
+        if let Some(delegate_record) = self.delegate_record {
+            builder.delegate_record(delegate_record);
+        }
+        if let Some(master_edition) = self.master_edition {
+            builder.master_edition(master_edition);
+        }
+        if let Some(token_record) = self.token_record {
+            builder.token_record(token_record);
+        }
+        if let Some(authorization_rules_program) = self.authorization_rules_program {
+            builder.authorization_rules_program(authorization_rules_program);
+        }
+        if let Some(authorization_rules) = self.authorization_rules {
+            builder.authorization_rules(authorization_rules);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingRevokeAccount))?
+            .instruction())
+    }
+
+    #[cfg(feature = "client")]
+    pub fn delegate_instruction(
+        &self,
+        args: DelegateArgs,
+    ) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build_delegate(args).map(with_cpi_accounts)
+    }
+
+    #[cfg(feature = "client")]
+    pub fn revoke_instruction(&self, args: RevokeArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build_revoke(args).map(with_cpi_accounts)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyKeys {
+    pub authority: Pubkey,
+    pub delegate_record: Option<Pubkey>,
+    pub metadata: Pubkey,
+    pub collection_mint: Option<Pubkey>,
+    pub collection_metadata: Option<Pubkey>,
+    pub collection_master_edition: Option<Pubkey>,
+    pub system_program: Pubkey,
+    pub sysvar_instructions: Pubkey,
+}
+
+impl VerifyKeys {
+    pub fn build(&self, args: VerificationArgs) -> Result<Instruction> {
+        let mut builder = VerifyBuilder::new();
+        builder
+            .authority(self.authority)
+            .metadata(self.metadata)
+            .system_program(self.system_program)
+            .sysvar_instructions(self.sysvar_instructions);
+
+        if let Some(delegate_record) = self.delegate_record {
+            builder.delegate_record(delegate_record);
+        }
+        if let Some(collection_mint) = self.collection_mint {
+            builder.collection_mint(collection_mint);
+        }
+        if let Some(collection_metadata) = self.collection_metadata {
+            builder.collection_metadata(collection_metadata);
+        }
+        if let Some(collection_master_edition) = self.collection_master_edition {
+            builder.collection_master_edition(collection_master_edition);
+        }
+
+        Ok(builder
+            .build(args)
+            .map_err(|err| builder_error(err, UtilErrors::MissingVerifyAccount))?
+            .instruction())
+    }
+
+    #[cfg(feature = "client")]
+    pub fn instruction(&self, args: VerificationArgs) -> Result<(Instruction, Vec<AccountMeta>)> {
+        self.build(args).map(with_cpi_accounts)
+    }
+}
@@ -1,14 +1,36 @@
+pub mod asset_metadata;
+pub mod authorization;
 pub mod bpf_writer;
+pub mod bubblegum;
 pub mod collection;
+pub mod compression;
+pub mod core;
 pub mod errors;
+#[cfg(all(feature = "test-support", not(target_os = "solana")))]
+pub mod fixtures;
+#[cfg(feature = "client")]
+pub mod instruction;
+#[cfg(not(feature = "client"))]
+pub(crate) mod instruction;
 pub mod merkle_tree;
 pub mod metadata;
+pub mod metadata_reader;
+pub mod pda;
+pub mod prelude;
+#[cfg(all(feature = "program-test", not(target_os = "solana")))]
+pub mod program_test;
+#[cfg(all(feature = "test-support", not(target_os = "solana")))]
+pub mod test_support;
+pub mod token_2022;
 pub mod traits;
+pub mod validation;
 
-pub use {bpf_writer::*, collection::*, errors::*, metadata::*, traits::*};
+pub use {
+    asset_metadata::*, authorization::*, bpf_writer::*, collection::*, errors::*, metadata::*,
+    metadata_reader::*, token_2022::*, traits::*, validation::*,
+};
 
-pub use {anchor_lang::solana_program, mpl_token_metadata};
-use {errors::UtilErrors, mpl_token_metadata::state::Metadata};
+pub use {mpl_bubblegum, mpl_core, mpl_token_metadata, solana_program};
 #[track_caller]
 #[inline(always)]
 pub const fn add_signed(a: usize, b: isize) -> usize {
@@ -18,83 +40,120 @@ pub const fn add_signed(a: usize, b: isize) -> usize {
     }
 }
 
+/// Resizes `account_info` by `len` bytes. Bytes exposed by growing it are not
+/// zeroed; see [`reallocate_to`].
 pub fn reallocate<'info>(
     len: isize,
     account_info: AccountInfo<'info>,
     payer_info: AccountInfo<'info>,
-    rent_sysvar: &Sysvar<'info, Rent>,
-    system_program: &Program<'info, System>,
+    rent_sysvar: &RentSysvar<'info>,
+    system_program: &SystemProgram<'info>,
+) -> Result<()> {
+    let new_len = add_signed(account_info.data_len(), len);
+    reallocate_to(
+        new_len,
+        false,
+        account_info,
+        payer_info,
+        rent_sysvar,
+        system_program,
+    )
+}
+
+/// Resizes `account_info` to `new_len` bytes, keeping it rent exempt: the
+/// payer funds growth and is refunded on shrink.
+///
+/// With `zero_init`, every byte exposed by growing is zeroed, including bytes
+/// left over from an earlier shrink in the same instruction.
+///
+/// An account can grow by at most `MAX_PERMITTED_DATA_INCREASE` (10 KiB) past
+/// its length at the start of the instruction, however the growth is split
+/// across calls; growing further fails with `ProgramError::InvalidRealloc`.
+pub fn reallocate_to<'info>(
+    new_len: usize,
+    zero_init: bool,
+    account_info: AccountInfo<'info>,
+    payer_info: AccountInfo<'info>,
+    rent_sysvar: &RentSysvar<'info>,
+    system_program: &SystemProgram<'info>,
+) -> Result<()> {
+    resize(
+        new_len,
+        zero_init,
+        account_info,
+        payer_info,
+        rent_sysvar,
+        AccountInfo::clone(system_program),
+    )
+}
+
+fn resize<'info>(
+    new_len: usize,
+    zero_init: bool,
+    account_info: AccountInfo<'info>,
+    payer_info: AccountInfo<'info>,
+    rent: &Rent,
+    system_program: AccountInfo<'info>,
 ) -> Result<()> {
     let curr_len = account_info.data_len();
-    let new_len = add_signed(curr_len, len);
-    let curr_rent = rent_sysvar.minimum_balance(curr_len);
-    let new_rent = rent_sysvar.minimum_balance(new_len);
-    let rent_diff: isize = isize::try_from(new_rent).unwrap() - isize::try_from(curr_rent).unwrap();
+    if new_len == curr_len {
+        return Ok(());
+    }
 
-    let account_info_borrow = account_info.clone();
-    if rent_diff > 0 {
+    let curr_rent = rent.minimum_balance(curr_len);
+    let new_rent = rent.minimum_balance(new_len);
+    if new_rent > curr_rent {
         solana_program::program::invoke(
             &solana_program::system_instruction::transfer(
                 payer_info.key,
-                account_info_borrow.key,
-                u64::try_from(rent_diff).unwrap(),
+                account_info.key,
+                new_rent - curr_rent,
             ),
-            &[
-                payer_info,
-                account_info_borrow,
-                system_program.to_account_info(),
-            ],
+            &[payer_info, account_info.clone(), system_program],
         )?;
-    } else if rent_diff < 0 {
-        let parsed_rent_diff = u64::try_from(rent_diff * -1).unwrap();
+    } else if new_rent < curr_rent {
+        let refund = curr_rent - new_rent;
 
         **payer_info.lamports.borrow_mut() = payer_info
             .lamports()
-            .checked_add(parsed_rent_diff)
+            .checked_add(refund)
             .ok_or(UtilErrors::Overflow)?;
 
         **account_info.lamports.borrow_mut() = account_info
             .lamports()
-            .checked_sub(parsed_rent_diff)
+            .checked_sub(refund)
             .ok_or(UtilErrors::Overflow)?;
-    } else {
-        return Ok(());
-    }
-    if len.wrapping_abs() as usize > solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE {
-        let mut updated_s = account_info.data_len().clone();
-        while updated_s < new_len {
-            updated_s += solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
-            if updated_s > new_len {
-                updated_s = new_len;
-            }
-            account_info.realloc(updated_s, false).unwrap()
-        }
-        while updated_s > new_len {
-            updated_s -= solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
-            if updated_s < new_len {
-                updated_s = new_len;
-            }
-            account_info.realloc(updated_s, false).unwrap()
-        }
-        Ok(())
-    } else {
-        account_info
-            .realloc(usize::try_from(new_len).unwrap(), false)
-            .map_err(Into::into)
     }
+
+    account_info.realloc(new_len, zero_init)?;
+    Ok(())
 }
 
 pub enum ValidateCollectionCreatorOutput {
     Collection { address: Pubkey },
     Creator { address: Pubkey },
 }
-pub fn validate_collection_creator<'info>(
-    metadata: Metadata,
-    collections: &Vec<Pubkey>,
-    creators: &Vec<Pubkey>,
+pub fn validate_collection_creator(
+    metadata: &MetadataReader,
+    collections: &[Pubkey],
+    creators: &[Pubkey],
+) -> Result<ValidateCollectionCreatorOutput> {
+    match_collection_creator(
+        metadata.collection()?,
+        metadata.creators()?.map(|list| list.iter()),
+        collections,
+        creators,
+    )
+}
+
+pub(crate) fn match_collection_creator(
+    collection: Option<mpl_token_metadata::state::Collection>,
+    metadata_creators: Option<impl IntoIterator<Item = mpl_token_metadata::state::Creator>>,
+    collections: &[Pubkey],
+    creators: &[Pubkey],
 ) -> Result<ValidateCollectionCreatorOutput> {
-    if collections.len() > 0 {
-        if let Some(collection) = metadata.collection {
+    if !collections.is_empty() {
+        if let Some(collection) = collection {
             if collection.verified && collections.contains(&collection.key) {
                 return Ok(ValidateCollectionCreatorOutput::Collection {
                     address: collection.key,
@@ -103,14 +162,14 @@ pub fn validate_collection_creator<'info>(
         }
     }
 
-    if creators.len() > 0 {
-        if let Some(metadata_creators) = metadata.data.creators {
+    if !creators.is_empty() {
+        if let Some(metadata_creators) = metadata_creators {
             let found = metadata_creators
-                .iter()
-                .position(|x| x.verified && creators.contains(&x.address));
-            if let Some(index) = found {
+                .into_iter()
+                .find(|x| x.verified && creators.contains(&x.address));
+            if let Some(creator) = found {
                 return Ok(ValidateCollectionCreatorOutput::Creator {
-                    address: metadata_creators[index].address,
+                    address: creator.address,
                 });
             }
         }
@@ -1,24 +1,405 @@
-use anchor_lang::solana_program::keccak::{hashv, Hash};
+use {
+    crate::prelude::*,
+    solana_program::keccak::{hashv, Hash},
+};
 
+/// Prefix of leaf hashes, keeping them from colliding with internal nodes.
+pub const LEAF_PREFIX: u8 = 0x00;
+/// Prefix of internal node hashes.
+pub const NODE_PREFIX: u8 = 0x01;
+
+#[deprecated(note = "hashes without domain separation, use `hash_leaf` or `hash_pair`")]
 pub fn create_node(vals: &[&[u8]]) -> Hash {
     hashv(vals)
 }
 
+/// A leaf of the tree as it is fed to the proof.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct Leaf(pub [u8; 32]);
+
+/// An internal node, root or proof element of the tree.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct Node(pub [u8; 32]);
+
+impl From<Leaf> for Node {
+    fn from(leaf: Leaf) -> Self {
+        Self(leaf.0)
+    }
+}
+
+/// Hashes leaf data with [`LEAF_PREFIX`].
+pub fn hash_leaf(vals: &[&[u8]]) -> Leaf {
+    let mut prefixed = Vec::with_capacity(vals.len() + 1);
+    prefixed.push(&[LEAF_PREFIX][..]);
+    prefixed.extend_from_slice(vals);
+    Leaf(hashv(&prefixed).0)
+}
+
+/// Hashes two sibling nodes into their parent with [`NODE_PREFIX`]. The
+/// pair is sorted first so proofs don't need to carry the position of each
+/// sibling.
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[&[NODE_PREFIX], a, b]).0
+    } else {
+        hashv(&[&[NODE_PREFIX], b, a]).0
+    }
+}
+
+/// How leaves of a tree were encoded.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleVersion {
+    /// Leaves are raw 32-byte values, as taken by [`verify_merkle`].
+    V1,
+    /// Leaves are the [`hash_leaf`] of their data.
+    V2,
+}
+
+impl MerkleVersion {
+    /// Encodes `data` as a leaf of this version. `V1` leaves must already be
+    /// 32 bytes long.
+    pub fn leaf(&self, data: &[u8]) -> Option<Leaf> {
+        match self {
+            Self::V1 => data.try_into().ok().map(Leaf),
+            Self::V2 => Some(hash_leaf(&[data])),
+        }
+    }
+}
+
 /// Returns true if a `leaf` can be proved to be a part of a Merkle tree
 /// defined by `root`. For this, a `proof` must be provided, containing
 /// sibling hashes on the branch from the leaf to the root of the tree. Each
 /// pair of leaves and each pair of pre-images are assumed to be sorted.
 pub fn verify_merkle(proof: Vec<[u8; 32]>, root: [u8; 32], leaf: [u8; 32]) -> bool {
+    verify_merkle_slice(&proof, root, leaf)
+}
+
+/// Same as [`verify_merkle`] without taking ownership of the proof.
+pub fn verify_merkle_slice(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
     let mut computed_hash = leaf;
-    for proof_element in proof.into_iter() {
-        if computed_hash <= proof_element {
-            // Hash(current computed hash + current element of the proof)
-            computed_hash = hashv(&[&[0x01], &computed_hash, &proof_element]).0;
-        } else {
-            // Hash(current element of the proof + current computed hash)
-            computed_hash = hashv(&[&[0x01], &proof_element, &computed_hash]).0;
-        }
+    for proof_element in proof {
+        computed_hash = hash_pair(&computed_hash, proof_element);
     }
     // Check if the computed hash (root) is equal to the provided root
     computed_hash == root
 }
+
+/// Returns true if a `leaf` at `index` can be proved to be a part of a
+/// Merkle tree defined by `root`, for trees hashing siblings by position
+/// rather than sorted and without node prefixes, such as SPL account
+/// compression trees. The bits of `index` tell, from the leaf up, whether
+/// the computed node is the left (`0`) or right (`1`) child.
+pub fn verify_merkle_indexed(
+    proof: &[[u8; 32]],
+    root: [u8; 32],
+    leaf: [u8; 32],
+    index: u32,
+) -> bool {
+    compute_root_indexed(proof.iter(), leaf, index) == Some(root)
+}
+
+fn compute_root_indexed<'a>(
+    proof: impl ExactSizeIterator<Item = &'a [u8; 32]>,
+    leaf: [u8; 32],
+    index: u32,
+) -> Option<[u8; 32]> {
+    if proof.len() < 32 && index >> proof.len() != 0 {
+        return None;
+    }
+    let mut computed_hash = leaf;
+    for (depth, sibling) in proof.enumerate() {
+        computed_hash = if (index >> depth) & 1 == 0 {
+            hashv(&[&computed_hash, sibling]).0
+        } else {
+            hashv(&[sibling, &computed_hash]).0
+        };
+    }
+    Some(computed_hash)
+}
+
+/// Zero-copy view over a proof laid out as consecutive 32-byte hashes, as it
+/// sits in instruction data.
+#[derive(Clone, Copy, Debug)]
+pub struct ProofView<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ProofView<'a> {
+    /// Returns `None` if `data` is not a whole number of hashes.
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        data.chunks_exact(32)
+            .remainder()
+            .is_empty()
+            .then_some(Self { data })
+    }
+
+    /// Reads a borsh encoded `Vec<[u8; 32]>` off the front of `data`,
+    /// returning the view and the remaining bytes.
+    pub fn from_prefixed(data: &'a [u8]) -> Option<(Self, &'a [u8])> {
+        let len = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+        let end = len.checked_mul(32)?.checked_add(4)?;
+        let proof = data.get(4..end)?;
+        Some((Self { data: proof }, &data[end..]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len() / 32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a [u8; 32]> {
+        let start = index.checked_mul(32)?;
+        self.data
+            .get(start..start.checked_add(32)?)?
+            .try_into()
+            .ok()
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &'a [u8; 32]> + 'a {
+        self.data
+            .chunks_exact(32)
+            .map(|chunk| chunk.try_into().unwrap())
+    }
+
+    /// See [`verify_merkle`].
+    pub fn verify(&self, root: [u8; 32], leaf: [u8; 32]) -> bool {
+        self.iter()
+            .fold(leaf, |computed, sibling| hash_pair(&computed, sibling))
+            == root
+    }
+
+    /// See [`verify_merkle_indexed`].
+    pub fn verify_indexed(&self, root: [u8; 32], leaf: [u8; 32], index: u32) -> bool {
+        compute_root_indexed(self.iter(), leaf, index) == Some(root)
+    }
+}
+
+/// Typed counterpart of [`verify_merkle`].
+pub fn verify_merkle_leaf(proof: &[Node], root: Node, leaf: Leaf) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf.0, |computed, sibling| hash_pair(&computed, &sibling.0));
+    computed == root.0
+}
+
+/// Returns true if `data`, encoded as a leaf of `version`, can be proved to
+/// be a part of the tree defined by `root`.
+pub fn verify_merkle_versioned(
+    version: MerkleVersion,
+    proof: Vec<[u8; 32]>,
+    root: [u8; 32],
+    data: &[u8],
+) -> bool {
+    match version.leaf(data) {
+        Some(leaf) => verify_merkle(proof, root, leaf.0),
+        None => false,
+    }
+}
+
+/// Returns true if all `leaves` can be proved together to be a part of the
+/// tree defined by `root`, hashing every shared branch only once.
+///
+/// `proof` holds the sibling hashes not computable from the leaves
+/// themselves, and `proof_flags` tells for each hashing step whether its
+/// second operand is the next computed node (`true`) or the next `proof`
+/// element (`false`). `leaves` must be ordered as returned by
+/// `MerkleTree::multiproof`.
+pub fn verify_multiproof(
+    proof: &[[u8; 32]],
+    proof_flags: &[bool],
+    root: [u8; 32],
+    leaves: &[[u8; 32]],
+) -> bool {
+    let total_hashes = proof_flags.len();
+    if leaves.len() + proof.len() != total_hashes + 1 {
+        return false;
+    }
+    if total_hashes == 0 {
+        return leaves.first().or(proof.first()) == Some(&root);
+    }
+
+    let mut hashes = Vec::with_capacity(total_hashes);
+    let (mut leaf_pos, mut hash_pos, mut proof_pos) = (0, 0, 0);
+    let mut next_node = |hashes: &Vec<[u8; 32]>| {
+        if leaf_pos < leaves.len() {
+            leaf_pos += 1;
+            Some(leaves[leaf_pos - 1])
+        } else {
+            hash_pos += 1;
+            hashes.get(hash_pos - 1).copied()
+        }
+    };
+
+    for flag in proof_flags {
+        let Some(a) = next_node(&hashes) else {
+            return false;
+        };
+        let b = if *flag {
+            next_node(&hashes)
+        } else {
+            proof_pos += 1;
+            proof.get(proof_pos - 1).copied()
+        };
+        let Some(b) = b else {
+            return false;
+        };
+        hashes.push(hash_pair(&a, &b));
+    }
+
+    proof_pos == proof.len() && hashes[total_hashes - 1] == root
+}
+
+/// Leaves and hashes proving several leaves at once, see
+/// [`verify_multiproof`].
+#[cfg(feature = "client")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiProof {
+    pub leaves: Vec<[u8; 32]>,
+    pub proof: Vec<[u8; 32]>,
+    pub proof_flags: Vec<bool>,
+}
+
+/// Host-side Merkle tree producing roots and proofs that [`verify_merkle`]
+/// accepts.
+///
+/// Nodes are stored as a complete binary tree in a flat array: the root is
+/// at index 0, the children of node `i` are at `2i + 1` and `2i + 2`, and the
+/// leaves fill the end of the array in reverse order.
+#[cfg(feature = "client")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    nodes: Vec<[u8; 32]>,
+    leaf_count: usize,
+}
+
+#[cfg(feature = "client")]
+// A tree has at least one leaf, so there is no `is_empty`.
+#[allow(clippy::len_without_is_empty)]
+impl MerkleTree {
+    /// Builds the tree over `leaves`, keeping their order, or returns `None`
+    /// if there are none.
+    pub fn new(leaves: Vec<[u8; 32]>) -> Option<Self> {
+        if leaves.is_empty() {
+            return None;
+        }
+
+        let leaf_count = leaves.len();
+        let mut nodes = vec![[0u8; 32]; 2 * leaf_count - 1];
+        for (index, leaf) in leaves.into_iter().enumerate() {
+            let position = nodes.len() - 1 - index;
+            nodes[position] = leaf;
+        }
+        for position in (0..leaf_count - 1).rev() {
+            nodes[position] = hash_pair(&nodes[2 * position + 1], &nodes[2 * position + 2]);
+        }
+
+        Some(Self { nodes, leaf_count })
+    }
+
+    /// Builds the tree over the encoding of each item for `version`, or
+    /// returns `None` if `data` is empty or a `V1` item is not 32 bytes long.
+    pub fn from_data<T: AsRef<[u8]>>(version: MerkleVersion, data: &[T]) -> Option<Self> {
+        Self::new(
+            data.iter()
+                .map(|item| version.leaf(item.as_ref()).map(|leaf| leaf.0))
+                .collect::<Option<_>>()?,
+        )
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.nodes[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaf_count
+    }
+
+    pub fn leaf(&self, index: usize) -> Option<[u8; 32]> {
+        self.leaf_position(index)
+            .map(|position| self.nodes[position])
+    }
+
+    pub fn leaves(&self) -> impl Iterator<Item = [u8; 32]> + '_ {
+        (0..self.leaf_count).map(|index| self.nodes[self.nodes.len() - 1 - index])
+    }
+
+    /// Returns the index of the first leaf equal to `leaf`.
+    pub fn position(&self, leaf: &[u8; 32]) -> Option<usize> {
+        self.leaves().position(|x| x == *leaf)
+    }
+
+    /// Returns the sibling hashes from the leaf at `index` up to the root.
+    pub fn proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        let mut position = self.leaf_position(index)?;
+        let mut proof = vec![];
+        while position > 0 {
+            let sibling = if position % 2 == 1 {
+                position + 1
+            } else {
+                position - 1
+            };
+            proof.push(self.nodes[sibling]);
+            position = (position - 1) / 2;
+        }
+        Some(proof)
+    }
+
+    /// Returns a proof for all leaves at `indices` at once, or `None` if an
+    /// index is out of bounds or repeated.
+    pub fn multiproof(&self, indices: &[usize]) -> Option<MultiProof> {
+        let mut positions = indices
+            .iter()
+            .map(|index| self.leaf_position(*index))
+            .collect::<Option<Vec<_>>>()?;
+        positions.sort_unstable_by(|a, b| b.cmp(a));
+        if positions.windows(2).any(|pair| pair[0] == pair[1]) {
+            return None;
+        }
+
+        let leaves = positions
+            .iter()
+            .map(|position| self.nodes[*position])
+            .collect();
+        let mut queue: std::collections::VecDeque<usize> = positions.into();
+        let mut proof = vec![];
+        let mut proof_flags = vec![];
+        while let Some(position) = queue.pop_front() {
+            if position == 0 {
+                break;
+            }
+            let sibling = if position % 2 == 1 {
+                position + 1
+            } else {
+                position - 1
+            };
+            if queue.front() == Some(&sibling) {
+                queue.pop_front();
+                proof_flags.push(true);
+            } else {
+                proof.push(self.nodes[sibling]);
+                proof_flags.push(false);
+            }
+            queue.push_back((position - 1) / 2);
+        }
+        if indices.is_empty() {
+            proof.push(self.root());
+        }
+
+        Some(MultiProof {
+            leaves,
+            proof,
+            proof_flags,
+        })
+    }
+
+    fn leaf_position(&self, index: usize) -> Option<usize> {
+        (index < self.leaf_count).then(|| self.nodes.len() - 1 - index)
+    }
+}
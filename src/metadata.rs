@@ -1,5 +1,26 @@
+use {
+    crate::{
+        authorization::AuthorizationData,
+        errors::{from_program_error, UtilErrors},
+        instruction::{
+            BurnKeys, CreateKeys, DelegateKeys, LockKeys, MintKeys, TransferKeys, UpdateKeys,
+            VerifyKeys,
+        },
+        metadata_reader::MetadataReader,
+        pda::{
+            assert_delegate_record, assert_edition, assert_edition_marker, assert_metadata,
+            assert_token_record, find_delegate_record,
+        },
+        token_2022::assert_token_program,
+    },
+    mpl_token_metadata::{
+        instruction::{CollectionToggle, MetadataDelegateRole},
+        state::{Edition, MetadataDelegateRecord, TokenMetadataAccount, TokenStandard},
+    },
+};
+
 pub use {
-    anchor_lang::{prelude::*, solana_program},
+    crate::prelude::*,
     mpl_token_metadata::{
         self,
         instruction::{
@@ -12,8 +33,1220 @@ pub use {
         },
         state::{AssetData, PrintSupply},
     },
+    solana_program,
 };
 
+pub(crate) fn invoke_instruction(
+    instruction: &solana_program::instruction::Instruction,
+    account_infos: &[AccountInfo],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    if let Some(signer_seeds) = signer_seeds {
+        solana_program::program::invoke_signed(instruction, account_infos, signer_seeds)
+            .map_err(from_program_error)
+    } else {
+        solana_program::program::invoke(instruction, account_infos).map_err(from_program_error)
+    }
+}
+
+fn token_standard(metadata: &AccountInfo) -> Result<Option<TokenStandard>> {
+    MetadataReader::new(&metadata.try_borrow_data()?)?.token_standard()
+}
+
+fn is_programmable(token_standard: Option<TokenStandard>) -> bool {
+    matches!(
+        token_standard,
+        Some(
+            TokenStandard::ProgrammableNonFungible | TokenStandard::ProgrammableNonFungibleEdition
+        )
+    )
+}
+
+fn has_edition(token_standard: Option<TokenStandard>) -> bool {
+    matches!(
+        token_standard,
+        Some(
+            TokenStandard::NonFungible
+                | TokenStandard::NonFungibleEdition
+                | TokenStandard::ProgrammableNonFungible
+                | TokenStandard::ProgrammableNonFungibleEdition
+        )
+    )
+}
+
+/// Token Metadata 1.13.2 only supports SPL Token, not Token-2022.
+fn assert_spl_token_program(token_program: &Pubkey) -> Result<()> {
+    if *token_program != spl_token::ID {
+        msg!("Token Metadata requires SPL Token, got {}", token_program);
+        return Err(UtilErrors::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
+fn assert_spl_token_mint(mint: &AccountInfo, token_program: &AccountInfo) -> Result<()> {
+    assert_spl_token_program(token_program.key)?;
+    assert_token_program(mint, token_program)
+}
+
+fn require_account(name: &str, account: &Option<AccountInfo>, error: UtilErrors) -> Result<()> {
+    if account.is_none() {
+        msg!("{} is required for this token standard", name);
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Runs `assert` on `account` when it is provided.
+fn assert_optional(
+    account: &Option<AccountInfo>,
+    assert: impl FnOnce(&AccountInfo) -> Result<u8>,
+) -> Result<()> {
+    if let Some(account) = account {
+        assert(account)?;
+    }
+    Ok(())
+}
+
+/// Like `assert_delegate_record`, but accepts the record of any of `mints`:
+/// some roles can be held over the asset or over its collection.
+fn assert_delegate_record_of_any(
+    account: &AccountInfo,
+    mints: &[Pubkey],
+    role: MetadataDelegateRole,
+    update_authority: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<()> {
+    let matches = mints.iter().any(|mint| {
+        find_delegate_record(mint, role, update_authority, delegate).0 == account.key()
+    });
+    if !matches {
+        msg!(
+            "delegate_record: not the {:?} record of {} over {:?}",
+            role,
+            delegate,
+            mints
+        );
+        return Err(UtilErrors::InvalidPda.into());
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct CreateAccounts<'info> {
+    pub metadata: AccountInfo<'info>,
+    pub master_edition: Option<AccountInfo<'info>>,
+    pub mint: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub update_authority: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub spl_token_program: AccountInfo<'info>,
+    pub initialize_mint: bool,
+    pub update_authority_as_signer: bool,
+}
+
+impl<'info> CreateAccounts<'info> {
+    fn keys(&self) -> CreateKeys {
+        CreateKeys {
+            metadata: self.metadata.key(),
+            master_edition: self.master_edition.as_ref().map(Key::key),
+            mint: self.mint.key(),
+            authority: self.authority.key(),
+            payer: self.payer.key(),
+            update_authority: self.update_authority.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            spl_token_program: self.spl_token_program.key(),
+            initialize_mint: self.initialize_mint,
+            update_authority_as_signer: self.update_authority_as_signer,
+        }
+    }
+
+    pub fn invoke(&self, args: CreateArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let create_ix = self.keys().build(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.metadata),
+            self.master_edition.as_ref(),
+            Some(&self.mint),
+            Some(&self.authority),
+            Some(&self.payer),
+            Some(&self.update_authority),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            Some(&self.spl_token_program),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&create_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when a non-fungible asset is created without its master
+    /// edition, when the metadata or master edition are not the mint's, or
+    /// from a mint that is not an SPL Token one.
+    pub fn check(&self, args: &CreateArgs) -> Result<()> {
+        if self.initialize_mint {
+            assert_spl_token_program(self.spl_token_program.key)?;
+        } else {
+            assert_spl_token_mint(&self.mint, &self.spl_token_program)?;
+        }
+        assert_metadata(&self.metadata, self.mint.key)?;
+        let CreateArgs::V1 { asset_data, .. } = args;
+        if has_edition(Some(asset_data.token_standard)) {
+            require_account(
+                "master_edition",
+                &self.master_edition,
+                UtilErrors::MissingEdition,
+            )?;
+        }
+        assert_optional(&self.master_edition, |edition| {
+            assert_edition(edition, self.mint.key)
+        })
+    }
+
+    pub fn invoke_checked(
+        &self,
+        args: CreateArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        self.check(&args)?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct MintAccounts<'info> {
+    pub token: AccountInfo<'info>,
+    pub token_owner: Option<AccountInfo<'info>>,
+    pub metadata: AccountInfo<'info>,
+    pub master_edition: Option<AccountInfo<'info>>,
+    pub token_record: Option<AccountInfo<'info>>,
+    pub mint: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub delegate_record: Option<AccountInfo<'info>>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub spl_token_program: AccountInfo<'info>,
+    pub spl_ata_program: AccountInfo<'info>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+impl<'info> MintAccounts<'info> {
+    fn keys(&self) -> MintKeys {
+        MintKeys {
+            token: self.token.key(),
+            token_owner: self.token_owner.as_ref().map(Key::key),
+            metadata: self.metadata.key(),
+            master_edition: self.master_edition.as_ref().map(Key::key),
+            token_record: self.token_record.as_ref().map(Key::key),
+            mint: self.mint.key(),
+            authority: self.authority.key(),
+            delegate_record: self.delegate_record.as_ref().map(Key::key),
+            payer: self.payer.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            spl_token_program: self.spl_token_program.key(),
+            spl_ata_program: self.spl_ata_program.key(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(Key::key),
+            authorization_rules: self.authorization_rules.as_ref().map(Key::key),
+        }
+    }
+
+    pub fn invoke(&self, args: MintArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let mint_ix = self.keys().build(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.token),
+            self.token_owner.as_ref(),
+            Some(&self.metadata),
+            self.master_edition.as_ref(),
+            self.token_record.as_ref(),
+            Some(&self.mint),
+            Some(&self.authority),
+            self.delegate_record.as_ref(),
+            Some(&self.payer),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            Some(&self.spl_token_program),
+            Some(&self.spl_ata_program),
+            self.authorization_rules_program.as_ref(),
+            self.authorization_rules.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&mint_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when the master edition or, for pNFTs, the token record
+    /// of `token` is missing or not the mint's.
+    pub fn check(&self) -> Result<()> {
+        assert_spl_token_mint(&self.mint, &self.spl_token_program)?;
+        assert_metadata(&self.metadata, self.mint.key)?;
+        let token_standard = token_standard(&self.metadata)?;
+        if has_edition(token_standard) {
+            require_account(
+                "master_edition",
+                &self.master_edition,
+                UtilErrors::MissingEdition,
+            )?;
+        }
+        if is_programmable(token_standard) {
+            require_account(
+                "token_record",
+                &self.token_record,
+                UtilErrors::MissingTokenRecord,
+            )?;
+        }
+        assert_optional(&self.master_edition, |edition| {
+            assert_edition(edition, self.mint.key)
+        })?;
+        assert_optional(&self.token_record, |token_record| {
+            assert_token_record(token_record, self.mint.key, self.token.key)
+        })
+    }
+
+    pub fn invoke_checked(&self, args: MintArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        self.check()?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct BurnAccounts<'info> {
+    pub authority: AccountInfo<'info>,
+    pub collection_metadata: Option<AccountInfo<'info>>,
+    pub metadata: AccountInfo<'info>,
+    pub edition: Option<AccountInfo<'info>>,
+    pub mint: AccountInfo<'info>,
+    pub token: AccountInfo<'info>,
+    pub master_edition: Option<AccountInfo<'info>>,
+    pub master_edition_mint: Option<AccountInfo<'info>>,
+    pub master_edition_token: Option<AccountInfo<'info>>,
+    pub edition_marker: Option<AccountInfo<'info>>,
+    pub token_record: Option<AccountInfo<'info>>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub spl_token_program: AccountInfo<'info>,
+}
+
+impl<'info> BurnAccounts<'info> {
+    fn keys(&self) -> BurnKeys {
+        BurnKeys {
+            authority: self.authority.key(),
+            collection_metadata: self.collection_metadata.as_ref().map(Key::key),
+            metadata: self.metadata.key(),
+            edition: self.edition.as_ref().map(Key::key),
+            mint: self.mint.key(),
+            token: self.token.key(),
+            master_edition: self.master_edition.as_ref().map(Key::key),
+            master_edition_mint: self.master_edition_mint.as_ref().map(Key::key),
+            master_edition_token: self.master_edition_token.as_ref().map(Key::key),
+            edition_marker: self.edition_marker.as_ref().map(Key::key),
+            token_record: self.token_record.as_ref().map(Key::key),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            spl_token_program: self.spl_token_program.key(),
+        }
+    }
+
+    pub fn invoke(&self, args: BurnArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let burn_ix = self.keys().build(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.authority),
+            self.collection_metadata.as_ref(),
+            Some(&self.metadata),
+            self.edition.as_ref(),
+            Some(&self.mint),
+            Some(&self.token),
+            self.master_edition.as_ref(),
+            self.master_edition_mint.as_ref(),
+            self.master_edition_token.as_ref(),
+            self.edition_marker.as_ref(),
+            self.token_record.as_ref(),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            Some(&self.spl_token_program),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&burn_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when the edition or, for pNFTs, the token record is
+    /// missing, or when any edition, marker, record or collection metadata
+    /// passed in is not the one Token Metadata derives.
+    pub fn check(&self) -> Result<()> {
+        assert_spl_token_mint(&self.mint, &self.spl_token_program)?;
+        assert_metadata(&self.metadata, self.mint.key)?;
+        let metadata_data = self.metadata.try_borrow_data()?;
+        let metadata = MetadataReader::new(&metadata_data)?;
+        let token_standard = metadata.token_standard()?;
+        if has_edition(token_standard) {
+            require_account("edition", &self.edition, UtilErrors::MissingEdition)?;
+        }
+        if is_programmable(token_standard) {
+            require_account(
+                "token_record",
+                &self.token_record,
+                UtilErrors::MissingTokenRecord,
+            )?;
+        }
+
+        assert_optional(&self.edition, |edition| {
+            assert_edition(edition, self.mint.key)
+        })?;
+        assert_optional(&self.token_record, |token_record| {
+            assert_token_record(token_record, self.mint.key, self.token.key)
+        })?;
+        if let Some(collection) = metadata.collection()? {
+            assert_optional(&self.collection_metadata, |collection_metadata| {
+                assert_metadata(collection_metadata, &collection.key)
+            })?;
+        }
+        // Print editions also name the master edition they were printed from.
+        if let Some(master_edition_mint) = &self.master_edition_mint {
+            assert_optional(&self.master_edition, |master_edition| {
+                assert_edition(master_edition, master_edition_mint.key)
+            })?;
+            if let (Some(edition), Some(edition_marker)) = (&self.edition, &self.edition_marker) {
+                let edition = Edition::from_account_info(edition)?.edition;
+                assert_edition_marker(edition_marker, master_edition_mint.key, edition)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn invoke_checked(&self, args: BurnArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        self.check()?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct UpdateAccounts<'info> {
+    pub delegate_record: Option<AccountInfo<'info>>,
+    pub token: Option<AccountInfo<'info>>,
+    pub mint: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub edition: Option<AccountInfo<'info>>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+impl<'info> UpdateAccounts<'info> {
+    fn keys(&self) -> UpdateKeys {
+        UpdateKeys {
+            authority: self.authority.key(),
+            delegate_record: self.delegate_record.as_ref().map(Key::key),
+            token: self.token.as_ref().map(Key::key),
+            mint: self.mint.key(),
+            metadata: self.metadata.key(),
+            edition: self.edition.as_ref().map(Key::key),
+            payer: self.payer.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(Key::key),
+            authorization_rules: self.authorization_rules.as_ref().map(Key::key),
+        }
+    }
+
+    pub fn invoke(&self, args: UpdateArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let update_ix = self.keys().build(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.authority),
+            self.delegate_record.as_ref(),
+            self.token.as_ref(),
+            Some(&self.mint),
+            Some(&self.metadata),
+            self.edition.as_ref(),
+            Some(&self.payer),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            self.authorization_rules_program.as_ref(),
+            self.authorization_rules.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&update_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when the mint is not an SPL Token one, or when the
+    /// edition or the delegate record `args` are signed for are not the
+    /// mint's.
+    pub fn check(&self, args: &UpdateArgs) -> Result<()> {
+        // Update takes no token program, so the mint's owner stands in for it.
+        assert_spl_token_program(self.mint.owner)?;
+        assert_metadata(&self.metadata, self.mint.key)?;
+        assert_optional(&self.edition, |edition| {
+            assert_edition(edition, self.mint.key)
+        })?;
+
+        let Some(delegate_record) = &self.delegate_record else {
+            return Ok(());
+        };
+        let metadata_data = self.metadata.try_borrow_data()?;
+        let metadata = MetadataReader::new(&metadata_data)?;
+        let collection_mint = match args {
+            UpdateArgs::AsCollectionDelegateV2 {
+                collection: CollectionToggle::Set(collection),
+                ..
+            }
+            | UpdateArgs::AsCollectionItemDelegateV2 {
+                collection: CollectionToggle::Set(collection),
+                ..
+            } => Some(collection.key),
+            _ => metadata.collection()?.map(|collection| collection.key),
+        };
+        // Data, collection and programmable config delegates may also hold
+        // their role over the collection.
+        let (role, over_collection) = match args {
+            UpdateArgs::AsAuthorityItemDelegateV2 { .. } => {
+                (MetadataDelegateRole::AuthorityItem, false)
+            }
+            UpdateArgs::AsDataItemDelegateV2 { .. } => (MetadataDelegateRole::DataItem, false),
+            UpdateArgs::AsCollectionItemDelegateV2 { .. } => {
+                (MetadataDelegateRole::CollectionItem, false)
+            }
+            UpdateArgs::AsProgrammableConfigItemDelegateV2 { .. } => {
+                (MetadataDelegateRole::ProgrammableConfigItem, false)
+            }
+            UpdateArgs::AsDataDelegateV2 { .. } => (MetadataDelegateRole::Data, true),
+            UpdateArgs::AsCollectionDelegateV2 { .. } => (MetadataDelegateRole::Collection, true),
+            UpdateArgs::AsProgrammableConfigDelegateV2 { .. } => {
+                (MetadataDelegateRole::ProgrammableConfig, true)
+            }
+            // The update authority signs these, so the record goes unused.
+            UpdateArgs::V1 { .. } | UpdateArgs::AsUpdateAuthorityV2 { .. } => return Ok(()),
+        };
+        let mut mints = vec![self.mint.key()];
+        if over_collection {
+            mints.extend(collection_mint);
+        }
+        assert_delegate_record_of_any(
+            delegate_record,
+            &mints,
+            role,
+            &metadata.update_authority(),
+            self.authority.key,
+        )
+    }
+
+    pub fn invoke_checked(
+        &self,
+        args: UpdateArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        self.check(&args)?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct TransferAccounts<'info> {
+    pub source_token_account: AccountInfo<'info>,
+    pub source_token_account_owner: AccountInfo<'info>,
+    pub destination_token_account: AccountInfo<'info>,
+    pub destination_token_account_owner: AccountInfo<'info>,
+    pub token_mint: AccountInfo<'info>,
+    pub token_metadata: AccountInfo<'info>,
+    pub token_edition: Option<AccountInfo<'info>>,
+    pub source_token_account_record: Option<AccountInfo<'info>>,
+    pub destination_token_account_record: Option<AccountInfo<'info>>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+impl<'info> TransferAccounts<'info> {
+    fn keys(&self) -> TransferKeys {
+        TransferKeys {
+            source_token_account: self.source_token_account.key(),
+            source_token_account_owner: self.source_token_account_owner.key(),
+            destination_token_account: self.destination_token_account.key(),
+            destination_token_account_owner: self.destination_token_account_owner.key(),
+            token_mint: self.token_mint.key(),
+            token_metadata: self.token_metadata.key(),
+            token_edition: self.token_edition.as_ref().map(Key::key),
+            source_token_account_record: self.source_token_account_record.as_ref().map(Key::key),
+            destination_token_account_record: self
+                .destination_token_account_record
+                .as_ref()
+                .map(Key::key),
+            authority: self.authority.key(),
+            payer: self.payer.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            token_program: self.token_program.key(),
+            associated_token_program: self.associated_token_program.key(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(Key::key),
+            authorization_rules: self.authorization_rules.as_ref().map(Key::key),
+        }
+    }
+
+    pub fn invoke(&self, args: TransferArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let transfer_ix = self.keys().build(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.source_token_account),
+            Some(&self.source_token_account_owner),
+            Some(&self.destination_token_account),
+            Some(&self.destination_token_account_owner),
+            Some(&self.token_mint),
+            Some(&self.token_metadata),
+            self.token_edition.as_ref(),
+            self.source_token_account_record.as_ref(),
+            self.destination_token_account_record.as_ref(),
+            Some(&self.authority),
+            Some(&self.payer),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            Some(&self.token_program),
+            Some(&self.associated_token_program),
+            self.authorization_rules_program.as_ref(),
+            self.authorization_rules.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&transfer_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when a pNFT is transferred without its edition and the
+    /// token records of both token accounts, or when any of those is not
+    /// the one Token Metadata derives.
+    pub fn check(&self) -> Result<()> {
+        assert_spl_token_mint(&self.token_mint, &self.token_program)?;
+        assert_metadata(&self.token_metadata, self.token_mint.key)?;
+        let token_standard = token_standard(&self.token_metadata)?;
+        if is_programmable(token_standard) {
+            require_account(
+                "token_edition",
+                &self.token_edition,
+                UtilErrors::MissingEdition,
+            )?;
+            require_account(
+                "source_token_account_record",
+                &self.source_token_account_record,
+                UtilErrors::MissingTokenRecord,
+            )?;
+            require_account(
+                "destination_token_account_record",
+                &self.destination_token_account_record,
+                UtilErrors::MissingTokenRecord,
+            )?;
+        }
+        assert_optional(&self.token_edition, |edition| {
+            assert_edition(edition, self.token_mint.key)
+        })?;
+        assert_optional(&self.source_token_account_record, |token_record| {
+            assert_token_record(
+                token_record,
+                self.token_mint.key,
+                self.source_token_account.key,
+            )
+        })?;
+        assert_optional(&self.destination_token_account_record, |token_record| {
+            assert_token_record(
+                token_record,
+                self.token_mint.key,
+                self.destination_token_account.key,
+            )
+        })
+    }
+
+    pub fn invoke_checked(
+        &self,
+        args: TransferArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        self.check()?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct LockAccounts<'info> {
+    pub authority: AccountInfo<'info>,
+    pub token_mint: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub token_account_owner: Option<AccountInfo<'info>>,
+    pub token_metadata: AccountInfo<'info>,
+    pub token_edition: Option<AccountInfo<'info>>,
+    pub token_record: Option<AccountInfo<'info>>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+impl<'info> LockAccounts<'info> {
+    fn keys(&self) -> LockKeys {
+        LockKeys {
+            authority: self.authority.key(),
+            token_account_owner: self.token_account_owner.as_ref().map(Key::key),
+            token_account: self.token_account.key(),
+            token_mint: self.token_mint.key(),
+            token_metadata: self.token_metadata.key(),
+            token_edition: self.token_edition.as_ref().map(Key::key),
+            token_record: self.token_record.as_ref().map(Key::key),
+            payer: self.payer.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            token_program: self.token_program.key(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(Key::key),
+            authorization_rules: self.authorization_rules.as_ref().map(Key::key),
+        }
+    }
+
+    pub fn invoke(&self, args: LockArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let lock_ix = self.keys().build_lock(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.authority),
+            self.token_account_owner.as_ref(),
+            Some(&self.token_account),
+            Some(&self.token_mint),
+            Some(&self.token_metadata),
+            self.token_edition.as_ref(),
+            self.token_record.as_ref(),
+            Some(&self.payer),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            Some(&self.token_program),
+            self.authorization_rules_program.as_ref(),
+            self.authorization_rules.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&lock_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when the edition or, for pNFTs, the token record of the
+    /// locked token account is missing or not the mint's.
+    pub fn check(&self) -> Result<()> {
+        assert_spl_token_mint(&self.token_mint, &self.token_program)?;
+        assert_metadata(&self.token_metadata, self.token_mint.key)?;
+        let token_standard = token_standard(&self.token_metadata)?;
+        if has_edition(token_standard) {
+            require_account(
+                "token_edition",
+                &self.token_edition,
+                UtilErrors::MissingEdition,
+            )?;
+        }
+        if is_programmable(token_standard) {
+            require_account(
+                "token_record",
+                &self.token_record,
+                UtilErrors::MissingTokenRecord,
+            )?;
+        }
+        assert_optional(&self.token_edition, |edition| {
+            assert_edition(edition, self.token_mint.key)
+        })?;
+        assert_optional(&self.token_record, |token_record| {
+            assert_token_record(token_record, self.token_mint.key, self.token_account.key)
+        })
+    }
+
+    pub fn invoke_checked(&self, args: LockArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        self.check()?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct UnlockAccounts<'info> {
+    pub authority: AccountInfo<'info>,
+    pub token_mint: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub token_account_owner: Option<AccountInfo<'info>>,
+    pub token_metadata: AccountInfo<'info>,
+    pub token_edition: Option<AccountInfo<'info>>,
+    pub token_record: Option<AccountInfo<'info>>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+impl<'info> UnlockAccounts<'info> {
+    fn keys(&self) -> LockKeys {
+        LockKeys {
+            authority: self.authority.key(),
+            token_account_owner: self.token_account_owner.as_ref().map(Key::key),
+            token_account: self.token_account.key(),
+            token_mint: self.token_mint.key(),
+            token_metadata: self.token_metadata.key(),
+            token_edition: self.token_edition.as_ref().map(Key::key),
+            token_record: self.token_record.as_ref().map(Key::key),
+            payer: self.payer.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            token_program: self.token_program.key(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(Key::key),
+            authorization_rules: self.authorization_rules.as_ref().map(Key::key),
+        }
+    }
+
+    pub fn invoke(&self, args: UnlockArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let unlock_ix = self.keys().build_unlock(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.authority),
+            self.token_account_owner.as_ref(),
+            Some(&self.token_account),
+            Some(&self.token_mint),
+            Some(&self.token_metadata),
+            self.token_edition.as_ref(),
+            self.token_record.as_ref(),
+            Some(&self.payer),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            Some(&self.token_program),
+            self.authorization_rules_program.as_ref(),
+            self.authorization_rules.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&unlock_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when the edition or, for pNFTs, the token record of the
+    /// token account being unlocked is missing or not the mint's.
+    pub fn check(&self) -> Result<()> {
+        assert_spl_token_mint(&self.token_mint, &self.token_program)?;
+        assert_metadata(&self.token_metadata, self.token_mint.key)?;
+        let token_standard = token_standard(&self.token_metadata)?;
+        if has_edition(token_standard) {
+            require_account(
+                "token_edition",
+                &self.token_edition,
+                UtilErrors::MissingEdition,
+            )?;
+        }
+        if is_programmable(token_standard) {
+            require_account(
+                "token_record",
+                &self.token_record,
+                UtilErrors::MissingTokenRecord,
+            )?;
+        }
+        assert_optional(&self.token_edition, |edition| {
+            assert_edition(edition, self.token_mint.key)
+        })?;
+        assert_optional(&self.token_record, |token_record| {
+            assert_token_record(token_record, self.token_mint.key, self.token_account.key)
+        })
+    }
+
+    pub fn invoke_checked(
+        &self,
+        args: UnlockArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        self.check()?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct DelegateAccounts<'info> {
+    pub delegate_record: Option<AccountInfo<'info>>,
+    pub delegate: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub master_edition: Option<AccountInfo<'info>>,
+    pub token_record: Option<AccountInfo<'info>>,
+    pub mint: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+impl<'info> DelegateAccounts<'info> {
+    fn keys(&self) -> DelegateKeys {
+        DelegateKeys {
+            delegate_record: self.delegate_record.as_ref().map(Key::key),
+            delegate: self.delegate.key(),
+            metadata: self.metadata.key(),
+            master_edition: self.master_edition.as_ref().map(Key::key),
+            token_record: self.token_record.as_ref().map(Key::key),
+            mint: self.mint.key(),
+            token_account: self.token_account.key(),
+            authority: self.authority.key(),
+            payer: self.payer.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            token_program: self.token_program.key(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(Key::key),
+            authorization_rules: self.authorization_rules.as_ref().map(Key::key),
+        }
+    }
+
+    pub fn invoke(&self, args: DelegateArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let delegate_ix = self.keys().build_delegate(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            self.delegate_record.as_ref(),
+            Some(&self.delegate),
+            Some(&self.metadata),
+            self.master_edition.as_ref(),
+            self.token_record.as_ref(),
+            Some(&self.mint),
+            Some(&self.token_account),
+            Some(&self.authority),
+            Some(&self.payer),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            Some(&self.token_program),
+            self.authorization_rules_program.as_ref(),
+            self.authorization_rules.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&delegate_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when the delegate record of a metadata delegate, or the
+    /// edition and token record of a pNFT token delegate, are missing or
+    /// not the ones Token Metadata derives for `delegate`.
+    pub fn check(&self, args: &DelegateArgs) -> Result<()> {
+        assert_spl_token_mint(&self.mint, &self.token_program)?;
+        assert_metadata(&self.metadata, self.mint.key)?;
+        let role = match args {
+            DelegateArgs::CollectionV1 { .. } => Some(MetadataDelegateRole::Collection),
+            DelegateArgs::DataV1 { .. } => Some(MetadataDelegateRole::Data),
+            DelegateArgs::ProgrammableConfigV1 { .. } => {
+                Some(MetadataDelegateRole::ProgrammableConfig)
+            }
+            DelegateArgs::AuthorityItemV1 { .. } => Some(MetadataDelegateRole::AuthorityItem),
+            DelegateArgs::DataItemV1 { .. } => Some(MetadataDelegateRole::DataItem),
+            DelegateArgs::CollectionItemV1 { .. } => Some(MetadataDelegateRole::CollectionItem),
+            DelegateArgs::ProgrammableConfigItemV1 { .. } => {
+                Some(MetadataDelegateRole::ProgrammableConfigItem)
+            }
+            DelegateArgs::SaleV1 { .. }
+            | DelegateArgs::TransferV1 { .. }
+            | DelegateArgs::UtilityV1 { .. }
+            | DelegateArgs::StakingV1 { .. }
+            | DelegateArgs::StandardV1 { .. }
+            | DelegateArgs::LockedTransferV1 { .. } => None,
+        };
+        let update_authority =
+            MetadataReader::new(&self.metadata.try_borrow_data()?)?.update_authority();
+        check_delegation(
+            role,
+            &update_authority,
+            &self.delegate_record,
+            &self.delegate,
+            &self.metadata,
+            &self.master_edition,
+            &self.token_record,
+            &self.mint,
+            &self.token_account,
+        )
+    }
+
+    pub fn invoke_checked(
+        &self,
+        args: DelegateArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        self.check(&args)?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[derive(Clone)]
+pub struct RevokeAccounts<'info> {
+    pub delegate_record: Option<AccountInfo<'info>>,
+    pub delegate: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub master_edition: Option<AccountInfo<'info>>,
+    pub token_record: Option<AccountInfo<'info>>,
+    pub mint: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+impl<'info> RevokeAccounts<'info> {
+    fn keys(&self) -> DelegateKeys {
+        DelegateKeys {
+            delegate_record: self.delegate_record.as_ref().map(Key::key),
+            delegate: self.delegate.key(),
+            metadata: self.metadata.key(),
+            master_edition: self.master_edition.as_ref().map(Key::key),
+            token_record: self.token_record.as_ref().map(Key::key),
+            mint: self.mint.key(),
+            token_account: self.token_account.key(),
+            authority: self.authority.key(),
+            payer: self.payer.key(),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+            token_program: self.token_program.key(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(Key::key),
+            authorization_rules: self.authorization_rules.as_ref().map(Key::key),
+        }
+    }
+
+    pub fn invoke(&self, args: RevokeArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let revoke_ix = self.keys().build_revoke(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            self.delegate_record.as_ref(),
+            Some(&self.delegate),
+            Some(&self.metadata),
+            self.master_edition.as_ref(),
+            self.token_record.as_ref(),
+            Some(&self.mint),
+            Some(&self.token_account),
+            Some(&self.authority),
+            Some(&self.payer),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+            Some(&self.token_program),
+            self.authorization_rules_program.as_ref(),
+            self.authorization_rules.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&revoke_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when the delegate record or pNFT token record being
+    /// revoked is missing or not the one of `delegate` over the mint.
+    pub fn check(&self, args: &RevokeArgs) -> Result<()> {
+        assert_spl_token_mint(&self.mint, &self.token_program)?;
+        assert_metadata(&self.metadata, self.mint.key)?;
+        let role = match args {
+            RevokeArgs::CollectionV1 => Some(MetadataDelegateRole::Collection),
+            RevokeArgs::DataV1 => Some(MetadataDelegateRole::Data),
+            RevokeArgs::ProgrammableConfigV1 => Some(MetadataDelegateRole::ProgrammableConfig),
+            RevokeArgs::AuthorityItemV1 => Some(MetadataDelegateRole::AuthorityItem),
+            RevokeArgs::DataItemV1 => Some(MetadataDelegateRole::DataItem),
+            RevokeArgs::CollectionItemV1 => Some(MetadataDelegateRole::CollectionItem),
+            RevokeArgs::ProgrammableConfigItemV1 => {
+                Some(MetadataDelegateRole::ProgrammableConfigItem)
+            }
+            RevokeArgs::SaleV1
+            | RevokeArgs::TransferV1
+            | RevokeArgs::UtilityV1
+            | RevokeArgs::StakingV1
+            | RevokeArgs::StandardV1
+            | RevokeArgs::LockedTransferV1
+            | RevokeArgs::MigrationV1 => None,
+        };
+        // A delegate revoking itself was approved by whoever was update
+        // authority then, which the record keeps.
+        let update_authority = match &self.delegate_record {
+            Some(delegate_record) if role.is_some() && self.delegate.key == self.authority.key => {
+                MetadataDelegateRecord::from_account_info(delegate_record)?.update_authority
+            }
+            _ => MetadataReader::new(&self.metadata.try_borrow_data()?)?.update_authority(),
+        };
+        check_delegation(
+            role,
+            &update_authority,
+            &self.delegate_record,
+            &self.delegate,
+            &self.metadata,
+            &self.master_edition,
+            &self.token_record,
+            &self.mint,
+            &self.token_account,
+        )
+    }
+
+    pub fn invoke_checked(
+        &self,
+        args: RevokeArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        self.check(&args)?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+/// Checks shared by `Delegate` and `Revoke`, which take the same accounts:
+/// metadata delegates (`role`) need their record, pNFT token delegates the
+/// edition and token record.
+#[allow(clippy::too_many_arguments)]
+fn check_delegation(
+    role: Option<MetadataDelegateRole>,
+    update_authority: &Pubkey,
+    delegate_record: &Option<AccountInfo>,
+    delegate: &AccountInfo,
+    metadata: &AccountInfo,
+    master_edition: &Option<AccountInfo>,
+    token_record: &Option<AccountInfo>,
+    mint: &AccountInfo,
+    token_account: &AccountInfo,
+) -> Result<()> {
+    if let Some(role) = role {
+        require_account(
+            "delegate_record",
+            delegate_record,
+            UtilErrors::MissingDelegateRecord,
+        )?;
+        return assert_optional(delegate_record, |delegate_record| {
+            assert_delegate_record(
+                delegate_record,
+                mint.key,
+                role,
+                update_authority,
+                delegate.key,
+            )
+        });
+    }
+
+    if is_programmable(token_standard(metadata)?) {
+        require_account("master_edition", master_edition, UtilErrors::MissingEdition)?;
+        require_account("token_record", token_record, UtilErrors::MissingTokenRecord)?;
+    }
+    assert_optional(master_edition, |edition| assert_edition(edition, mint.key))?;
+    assert_optional(token_record, |token_record| {
+        assert_token_record(token_record, mint.key, token_account.key)
+    })
+}
+
+#[derive(Clone)]
+pub struct VerifyAccounts<'info> {
+    pub authority: AccountInfo<'info>,
+    pub delegate_record: Option<AccountInfo<'info>>,
+    pub metadata: AccountInfo<'info>,
+    pub collection_mint: Option<AccountInfo<'info>>,
+    pub collection_metadata: Option<AccountInfo<'info>>,
+    pub collection_master_edition: Option<AccountInfo<'info>>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+}
+
+impl<'info> VerifyAccounts<'info> {
+    fn keys(&self) -> VerifyKeys {
+        VerifyKeys {
+            authority: self.authority.key(),
+            delegate_record: self.delegate_record.as_ref().map(Key::key),
+            metadata: self.metadata.key(),
+            collection_mint: self.collection_mint.as_ref().map(Key::key),
+            collection_metadata: self.collection_metadata.as_ref().map(Key::key),
+            collection_master_edition: self.collection_master_edition.as_ref().map(Key::key),
+            system_program: self.system_program.key(),
+            sysvar_instructions: self.sysvar_instructions.key(),
+        }
+    }
+
+    pub fn invoke(&self, args: VerificationArgs, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        let verify_ix = self.keys().build(args)?;
+        let account_infos: Vec<AccountInfo<'info>> = [
+            Some(&self.authority),
+            self.delegate_record.as_ref(),
+            Some(&self.metadata),
+            self.collection_mint.as_ref(),
+            self.collection_metadata.as_ref(),
+            self.collection_master_edition.as_ref(),
+            Some(&self.system_program),
+            Some(&self.sysvar_instructions),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        invoke_instruction(&verify_ix, &account_infos, signer_seeds)
+    }
+
+    /// Fails early when verifying a collection without the collection mint,
+    /// metadata and master edition, or when those or the collection
+    /// delegate record do not derive from the collection mint.
+    pub fn check(&self, args: &VerificationArgs) -> Result<()> {
+        if matches!(args, VerificationArgs::CreatorV1) {
+            return Ok(());
+        }
+        let (Some(collection_mint), Some(collection_metadata)) =
+            (&self.collection_mint, &self.collection_metadata)
+        else {
+            msg!("collection_mint and collection_metadata are required to verify a collection");
+            return Err(UtilErrors::MissingVerifyAccount.into());
+        };
+        require_account(
+            "collection_master_edition",
+            &self.collection_master_edition,
+            UtilErrors::MissingEdition,
+        )?;
+        assert_metadata(collection_metadata, collection_mint.key)?;
+        assert_optional(&self.collection_master_edition, |edition| {
+            assert_edition(edition, collection_mint.key)
+        })?;
+        assert_optional(&self.delegate_record, |delegate_record| {
+            let update_authority =
+                MetadataReader::new(&collection_metadata.try_borrow_data()?)?.update_authority();
+            assert_delegate_record(
+                delegate_record,
+                collection_mint.key,
+                MetadataDelegateRole::Collection,
+                &update_authority,
+                self.authority.key,
+            )
+        })
+    }
+
+    pub fn invoke_checked(
+        &self,
+        args: VerificationArgs,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        self.check(&args)?;
+        self.invoke(args, signer_seeds)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn create_nft<'info>(
     asset_data: AssetData,
     initialize_mint: bool,
@@ -27,7 +1260,7 @@ pub fn create_nft<'info>(
     system_program: AccountInfo<'info>,
     sysvar_instructions: AccountInfo<'info>,
     spl_token_program: AccountInfo<'info>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
     create(
         CreateArgs::V1 {
@@ -50,6 +1283,7 @@ pub fn create_nft<'info>(
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create<'info>(
     args: CreateArgs,
     initialize_mint: bool,
@@ -63,56 +1297,25 @@ pub fn create<'info>(
     system_program: AccountInfo<'info>,
     sysvar_instructions: AccountInfo<'info>,
     spl_token_program: AccountInfo<'info>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = CreateBuilder::new();
-    let create_builder = binding
-        .metadata(metadata.key())
-        .mint(mint.key())
-        .authority(authority.key())
-        .payer(payer.key())
-        .update_authority(update_authority.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key())
-        .spl_token_program(spl_token_program.key())
-        .initialize_mint(initialize_mint)
-        .update_authority_as_signer(update_authority_as_signer);
-
-    let mut account_infos = vec![metadata];
-
-    if let Some(master_edition) = master_edition {
-        create_builder.master_edition(master_edition.key());
-        account_infos.push(master_edition);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![
-            mint,
-            authority,
-            payer,
-            update_authority,
-            system_program,
-            sysvar_instructions,
-            spl_token_program,
-        ],
-    ]
-    .concat();
-
-    let create_ix = create_builder.build(args).unwrap().instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(
-            &create_ix,
-            &account_infos[..],
-            signer_seeds,
-        )
-        .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&create_ix, &account_infos[..]).map_err(Into::into);
+    CreateAccounts {
+        metadata,
+        master_edition,
+        mint,
+        authority,
+        payer,
+        update_authority,
+        system_program,
+        sysvar_instructions,
+        spl_token_program,
+        initialize_mint,
+        update_authority_as_signer,
     }
+    .invoke(args, signer_seeds)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn mint<'info>(
     args: MintArgs,
     token: AccountInfo<'info>,
@@ -130,79 +1333,29 @@ pub fn mint<'info>(
     spl_ata_program: AccountInfo<'info>,
     authorization_rules_program: Option<AccountInfo<'info>>,
     authorization_rules: Option<AccountInfo<'info>>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = MintBuilder::new();
-
-    let mint_builder = binding
-        .token(token.key())
-        .metadata(metadata.key())
-        .mint(mint.key())
-        .authority(authority.key())
-        .payer(payer.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key())
-        .spl_token_program(spl_token_program.key())
-        .spl_ata_program(spl_ata_program.key());
-
-    let mut account_infos = vec![token];
-
-    if let Some(token_owner) = token_owner {
-        mint_builder.token_owner(token_owner.key());
-        account_infos.push(token_owner);
-    }
-
-    account_infos = [account_infos, vec![metadata]].concat();
-
-    if let Some(master_edition) = master_edition {
-        mint_builder.master_edition(master_edition.key());
-        account_infos.push(master_edition);
-    }
-
-    if let Some(token_record) = token_record {
-        mint_builder.token_record(token_record.key());
-        account_infos.push(token_record);
-    }
-
-    account_infos = [account_infos, vec![mint, authority]].concat();
-
-    if let Some(delegate_record) = delegate_record {
-        mint_builder.delegate_record(delegate_record.key());
-        account_infos.push(delegate_record);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![
-            payer,
-            system_program,
-            sysvar_instructions,
-            spl_token_program,
-            spl_ata_program,
-        ],
-    ]
-    .concat();
-
-    if let Some(authorization_rules_program) = authorization_rules_program {
-        mint_builder.authorization_rules_program(authorization_rules_program.key());
-        account_infos.push(authorization_rules_program);
-    }
-
-    if let Some(authorization_rules) = authorization_rules {
-        mint_builder.authorization_rules(authorization_rules.key());
-        account_infos.push(authorization_rules);
-    }
-
-    let mint_ix = mint_builder.build(args).unwrap().instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(&mint_ix, &account_infos[..], signer_seeds)
-            .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&mint_ix, &account_infos[..]).map_err(Into::into);
+    MintAccounts {
+        token,
+        token_owner,
+        metadata,
+        master_edition,
+        token_record,
+        mint,
+        authority,
+        delegate_record,
+        payer,
+        system_program,
+        sysvar_instructions,
+        spl_token_program,
+        spl_ata_program,
+        authorization_rules_program,
+        authorization_rules,
     }
+    .invoke(args, signer_seeds)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn burn<'info>(
     args: BurnArgs,
     authority: AccountInfo<'info>,
@@ -219,76 +1372,28 @@ pub fn burn<'info>(
     system_program: AccountInfo<'info>,
     sysvar_instructions: AccountInfo<'info>,
     spl_token_program: AccountInfo<'info>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = BurnBuilder::new();
-
-    let burn_builder = binding
-        .authority(authority.key())
-        .metadata(metadata.key())
-        .mint(mint.key())
-        .token(token.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key())
-        .spl_token_program(spl_token_program.key());
-
-    let mut account_infos = vec![authority];
-
-    if let Some(collection_metadata) = collection_metadata {
-        burn_builder.collection_metadata(collection_metadata.key());
-        account_infos.push(collection_metadata);
-    }
-
-    account_infos = [account_infos, vec![metadata]].concat();
-
-    if let Some(edition) = edition {
-        burn_builder.edition(edition.key());
-        account_infos.push(edition);
-    }
-
-    account_infos = [account_infos, vec![mint, token]].concat();
-
-    if let Some(master_edition) = master_edition {
-        burn_builder.master_edition(master_edition.key());
-        account_infos.push(master_edition);
-    }
-
-    if let Some(master_edition_mint) = master_edition_mint {
-        burn_builder.master_edition_mint(master_edition_mint.key());
-        account_infos.push(master_edition_mint);
-    }
-
-    if let Some(master_edition_token) = master_edition_token {
-        burn_builder.master_edition_token(master_edition_token.key());
-        account_infos.push(master_edition_token);
-    }
-
-    if let Some(edition_marker) = edition_marker {
-        burn_builder.edition_marker(edition_marker.key());
-        account_infos.push(edition_marker);
-    }
-
-    if let Some(token_record) = token_record {
-        burn_builder.token_record(token_record.key());
-        account_infos.push(token_record);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![system_program, sysvar_instructions, spl_token_program],
-    ]
-    .concat();
-
-    let burn_ix = burn_builder.build(args).unwrap().instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(&burn_ix, &account_infos[..], signer_seeds)
-            .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&burn_ix, &account_infos[..]).map_err(Into::into);
+    BurnAccounts {
+        authority,
+        collection_metadata,
+        metadata,
+        edition,
+        mint,
+        token,
+        master_edition,
+        master_edition_mint,
+        master_edition_token,
+        edition_marker,
+        token_record,
+        system_program,
+        sysvar_instructions,
+        spl_token_program,
     }
+    .invoke(args, signer_seeds)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update<'info>(
     args: UpdateArgs,
     delegate_record: Option<AccountInfo<'info>>,
@@ -302,66 +1407,25 @@ pub fn update<'info>(
     sysvar_instructions: AccountInfo<'info>,
     authorization_rules_program: Option<AccountInfo<'info>>,
     authorization_rules: Option<AccountInfo<'info>>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = UpdateBuilder::new();
-    let update_builder = binding
-        .authority(authority.key())
-        .mint(mint.key())
-        .metadata(metadata.key())
-        .payer(payer.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key());
-
-    let mut account_infos = vec![authority];
-
-    if let Some(delegate_record) = delegate_record {
-        update_builder.delegate_record(delegate_record.key());
-        account_infos.push(delegate_record);
-    }
-
-    if let Some(token) = token {
-        update_builder.token(token.key());
-        account_infos.push(token);
-    }
-
-    account_infos = [account_infos, vec![mint, metadata]].concat();
-
-    if let Some(edition) = edition {
-        update_builder.edition(edition.key());
-        account_infos.push(edition);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![payer, system_program, sysvar_instructions],
-    ]
-    .concat();
-
-    if let Some(authorization_rules_program) = authorization_rules_program {
-        update_builder.authorization_rules_program(authorization_rules_program.key());
-        account_infos.push(authorization_rules_program);
-    }
-
-    if let Some(authorization_rules) = authorization_rules {
-        update_builder.authorization_rules(authorization_rules.key());
-        account_infos.push(authorization_rules);
-    }
-
-    let update_id = update_builder.build(args).unwrap().instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(
-            &update_id,
-            &account_infos[..],
-            signer_seeds,
-        )
-        .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&update_id, &account_infos[..]).map_err(Into::into);
+    UpdateAccounts {
+        delegate_record,
+        token,
+        mint,
+        metadata,
+        edition,
+        authority,
+        payer,
+        system_program,
+        sysvar_instructions,
+        authorization_rules_program,
+        authorization_rules,
     }
+    .invoke(args, signer_seeds)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn transfer<'info>(
     amount: u64,
     source_token_account: AccountInfo<'info>,
@@ -381,91 +1445,38 @@ pub fn transfer<'info>(
     sysvar_instructions: AccountInfo<'info>,
     authorization_rules_program: Option<AccountInfo<'info>>,
     authorization_rules: Option<AccountInfo<'info>>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    authorization_data: Option<AuthorizationData>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = TransferBuilder::new();
-    let transfer_builder = binding
-        .token(source_token_account.key())
-        .token_owner(source_token_account_owner.key())
-        .destination(destination_token_account.key())
-        .destination_owner(destination_token_account_owner.key())
-        .mint(token_mint.key())
-        .metadata(token_metadata.key())
-        .authority(authority.key())
-        .payer(payer.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key())
-        .spl_token_program(token_program.key())
-        .spl_ata_program(associated_token_program.key());
-
-    let mut account_infos = vec![
+    TransferAccounts {
         source_token_account,
         source_token_account_owner,
         destination_token_account,
         destination_token_account_owner,
         token_mint,
         token_metadata,
-    ];
-
-    if let Some(token_edition) = token_edition {
-        transfer_builder.edition(token_edition.key());
-        account_infos.push(token_edition);
-    }
-
-    if let Some(source_token_account_record) = source_token_account_record {
-        transfer_builder.owner_token_record(source_token_account_record.key());
-        account_infos.push(source_token_account_record);
-    }
-
-    if let Some(destination_token_account_record) = destination_token_account_record {
-        transfer_builder.destination_token_record(destination_token_account_record.key());
-        account_infos.push(destination_token_account_record);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![
-            authority,
-            payer,
-            system_program,
-            sysvar_instructions,
-            token_program,
-            associated_token_program,
-        ],
-    ]
-    .concat();
-
-    if let Some(authorization_rules_program) = authorization_rules_program {
-        transfer_builder.authorization_rules_program(authorization_rules_program.key());
-        account_infos.push(authorization_rules_program);
-    }
-
-    if let Some(authorization_rules) = authorization_rules {
-        transfer_builder.authorization_rules(authorization_rules.key());
-        account_infos.push(authorization_rules);
+        token_edition,
+        source_token_account_record,
+        destination_token_account_record,
+        authority,
+        payer,
+        system_program,
+        token_program,
+        associated_token_program,
+        sysvar_instructions,
+        authorization_rules_program,
+        authorization_rules,
     }
-
-    let transfer_ix = transfer_builder
-        .build(TransferArgs::V1 {
+    .invoke(
+        TransferArgs::V1 {
             amount,
-            authorization_data: None,
-        })
-        .unwrap()
-        .instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(
-            &transfer_ix,
-            &account_infos[..],
-            signer_seeds,
-        )
-        .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&transfer_ix, &account_infos[..])
-            .map_err(Into::into);
-    }
+            authorization_data,
+        },
+        signer_seeds,
+    )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn lock<'info>(
     authority: AccountInfo<'info>,
     token_mint: AccountInfo<'info>,
@@ -480,73 +1491,28 @@ pub fn lock<'info>(
     token_program: AccountInfo<'info>,
     authorization_rules_program: Option<AccountInfo<'info>>,
     authorization_rules: Option<AccountInfo<'info>>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    authorization_data: Option<AuthorizationData>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = LockBuilder::new();
-    let lock_builder = binding
-        .authority(authority.key())
-        .token(token_account.key())
-        .mint(token_mint.key())
-        .metadata(token_metadata.key())
-        .payer(payer.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key())
-        .spl_token_program(token_program.key());
-
-    let mut account_infos = vec![authority];
-
-    if let Some(token_account_owner) = token_account_owner {
-        lock_builder.token_owner(token_account_owner.key());
-        account_infos.push(token_account_owner);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![token_account, token_mint, token_metadata],
-    ]
-    .concat();
-
-    if let Some(token_edition) = token_edition {
-        lock_builder.edition(token_edition.key());
-        account_infos.push(token_edition);
-    }
-
-    if let Some(token_record) = token_record {
-        lock_builder.token_record(token_record.key());
-        account_infos.push(token_record);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![payer, system_program, sysvar_instructions, token_program],
-    ]
-    .concat();
-
-    if let Some(authorization_rules_program) = authorization_rules_program {
-        lock_builder.authorization_rules_program(authorization_rules_program.key());
-        account_infos.push(authorization_rules_program);
-    }
-
-    if let Some(authorization_rules) = authorization_rules {
-        lock_builder.authorization_rules(authorization_rules.key());
-        account_infos.push(authorization_rules);
-    }
-
-    let lock_ix = lock_builder
-        .build(LockArgs::V1 {
-            authorization_data: None,
-        })
-        .unwrap()
-        .instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(&lock_ix, &account_infos[..], signer_seeds)
-            .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&lock_ix, &account_infos[..]).map_err(Into::into);
+    LockAccounts {
+        authority,
+        token_mint,
+        token_account,
+        token_account_owner,
+        token_metadata,
+        token_edition,
+        token_record,
+        payer,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        authorization_rules_program,
+        authorization_rules,
     }
+    .invoke(LockArgs::V1 { authorization_data }, signer_seeds)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn unlock<'info>(
     authority: AccountInfo<'info>,
     token_mint: AccountInfo<'info>,
@@ -561,77 +1527,28 @@ pub fn unlock<'info>(
     token_program: AccountInfo<'info>,
     authorization_rules_program: Option<AccountInfo<'info>>,
     authorization_rules: Option<AccountInfo<'info>>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    authorization_data: Option<AuthorizationData>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = UnlockBuilder::new();
-    let unlock_builder = binding
-        .authority(authority.key())
-        .token(token_account.key())
-        .mint(token_mint.key())
-        .metadata(token_metadata.key())
-        .payer(payer.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key())
-        .spl_token_program(token_program.key());
-
-    let mut account_infos = vec![authority];
-
-    if let Some(token_account_owner) = token_account_owner {
-        unlock_builder.token_owner(token_account_owner.key());
-        account_infos.push(token_account_owner);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![token_account, token_mint, token_metadata],
-    ]
-    .concat();
-
-    if let Some(token_edition) = token_edition {
-        unlock_builder.edition(token_edition.key());
-        account_infos.push(token_edition);
-    }
-
-    if let Some(token_record) = token_record {
-        unlock_builder.token_record(token_record.key());
-        account_infos.push(token_record);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![payer, system_program, sysvar_instructions, token_program],
-    ]
-    .concat();
-
-    if let Some(authorization_rules_program) = authorization_rules_program {
-        unlock_builder.authorization_rules_program(authorization_rules_program.key());
-        account_infos.push(authorization_rules_program);
-    }
-
-    if let Some(authorization_rules) = authorization_rules {
-        unlock_builder.authorization_rules(authorization_rules.key());
-        account_infos.push(authorization_rules);
-    }
-
-    let unlock_ix = unlock_builder
-        .build(UnlockArgs::V1 {
-            authorization_data: None,
-        })
-        .unwrap()
-        .instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(
-            &unlock_ix,
-            &account_infos[..],
-            signer_seeds,
-        )
-        .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&unlock_ix, &account_infos[..]).map_err(Into::into);
+    UnlockAccounts {
+        authority,
+        token_mint,
+        token_account,
+        token_account_owner,
+        token_metadata,
+        token_edition,
+        token_record,
+        payer,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        authorization_rules_program,
+        authorization_rules,
     }
+    .invoke(UnlockArgs::V1 { authorization_data }, signer_seeds)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn delegate<'info>(
     args: DelegateArgs,
     delegate_record: Option<AccountInfo<'info>>,
@@ -648,78 +1565,28 @@ pub fn delegate<'info>(
     token_program: AccountInfo<'info>,
     authorization_rules_program: Option<AccountInfo<'info>>,
     authorization_rules: Option<AccountInfo<'info>>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = DelegateBuilder::new();
-    let delegate_builder = binding
-        .delegate(delegate.key())
-        .metadata(metadata.key())
-        .mint(mint.key())
-        .token(token_account.key())
-        .authority(authority.key())
-        .payer(payer.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key())
-        .spl_token_program(token_program.key());
-
-    let mut account_infos = vec![];
-
-    if let Some(delegate_record) = delegate_record {
-        delegate_builder.delegate_record(delegate_record.key());
-        account_infos.push(delegate_record);
-    }
-
-    account_infos = [account_infos, vec![delegate, metadata]].concat();
-
-    if let Some(master_edition) = master_edition {
-        delegate_builder.master_edition(master_edition.key());
-        account_infos.push(master_edition);
-    }
-
-    if let Some(token_record) = token_record {
-        delegate_builder.token_record(token_record.key());
-        account_infos.push(token_record);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![
-            mint,
-            token_account,
-            authority,
-            payer,
-            system_program,
-            sysvar_instructions,
-            token_program,
-        ],
-    ]
-    .concat();
-
-    if let Some(authorization_rules_program) = authorization_rules_program {
-        delegate_builder.authorization_rules_program(authorization_rules_program.key());
-        account_infos.push(authorization_rules_program);
-    }
-
-    if let Some(authorization_rules) = authorization_rules {
-        delegate_builder.authorization_rules(authorization_rules.key());
-        account_infos.push(authorization_rules);
-    }
-
-    let delegate_ix = delegate_builder.build(args).unwrap().instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(
-            &delegate_ix,
-            &account_infos[..],
-            signer_seeds,
-        )
-        .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&delegate_ix, &account_infos[..])
-            .map_err(Into::into);
+    DelegateAccounts {
+        delegate_record,
+        delegate,
+        metadata,
+        master_edition,
+        token_record,
+        mint,
+        token_account,
+        authority,
+        payer,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        authorization_rules_program,
+        authorization_rules,
     }
+    .invoke(args, signer_seeds)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn revoke<'info>(
     args: RevokeArgs,
     delegate_record: Option<AccountInfo<'info>>,
@@ -736,77 +1603,28 @@ pub fn revoke<'info>(
     token_program: AccountInfo<'info>,
     authorization_rules_program: Option<AccountInfo<'info>>,
     authorization_rules: Option<AccountInfo<'info>>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = RevokeBuilder::new();
-    let revoke_builder = binding
-        .delegate(delegate.key())
-        .metadata(metadata.key())
-        .mint(mint.key())
-        .token(token_account.key())
-        .authority(authority.key())
-        .payer(payer.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key())
-        .spl_token_program(token_program.key());
-
-    let mut account_infos = vec![];
-
-    if let Some(delegate_record) = delegate_record {
-        revoke_builder.delegate_record(delegate_record.key());
-        account_infos.push(delegate_record);
-    }
-
-    account_infos = [account_infos, vec![delegate, metadata]].concat();
-
-    if let Some(master_edition) = master_edition {
-        revoke_builder.master_edition(master_edition.key());
-        account_infos.push(master_edition);
-    }
-
-    if let Some(token_record) = token_record {
-        revoke_builder.token_record(token_record.key());
-        account_infos.push(token_record);
-    }
-
-    account_infos = [
-        account_infos,
-        vec![
-            mint,
-            token_account,
-            authority,
-            payer,
-            system_program,
-            sysvar_instructions,
-            token_program,
-        ],
-    ]
-    .concat();
-
-    if let Some(authorization_rules_program) = authorization_rules_program {
-        revoke_builder.authorization_rules_program(authorization_rules_program.key());
-        account_infos.push(authorization_rules_program);
-    }
-
-    if let Some(authorization_rules) = authorization_rules {
-        revoke_builder.authorization_rules(authorization_rules.key());
-        account_infos.push(authorization_rules);
-    }
-
-    let revoke_ix = revoke_builder.build(args).unwrap().instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(
-            &revoke_ix,
-            &account_infos[..],
-            signer_seeds,
-        )
-        .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&revoke_ix, &account_infos[..]).map_err(Into::into);
+    RevokeAccounts {
+        delegate_record,
+        delegate,
+        metadata,
+        master_edition,
+        token_record,
+        mint,
+        token_account,
+        authority,
+        payer,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        authorization_rules_program,
+        authorization_rules,
     }
+    .invoke(args, signer_seeds)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn verify<'info>(
     args: VerificationArgs,
     authority: AccountInfo<'info>,
@@ -817,51 +1635,17 @@ pub fn verify<'info>(
     collection_master_edition: Option<AccountInfo<'info>>,
     system_program: AccountInfo<'info>,
     sysvar_instructions: AccountInfo<'info>,
-    signer_seeds: Option<&[&[&[u8]]; 1]>,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    let mut binding = VerifyBuilder::new();
-    let verify_builder = binding
-        .authority(authority.key())
-        .metadata(metadata.key())
-        .system_program(system_program.key())
-        .sysvar_instructions(sysvar_instructions.key());
-
-    let mut account_infos = vec![authority];
-
-    if let Some(delegate_record) = delegate_record {
-        verify_builder.delegate_record(delegate_record.key());
-        account_infos.push(delegate_record);
-    }
-
-    account_infos = [account_infos, vec![metadata]].concat();
-
-    if let Some(collection_mint) = collection_mint {
-        verify_builder.collection_mint(collection_mint.key());
-        account_infos.push(collection_mint);
-    }
-
-    if let Some(collection_metadata) = collection_metadata {
-        verify_builder.collection_metadata(collection_metadata.key());
-        account_infos.push(collection_metadata);
-    }
-
-    if let Some(collection_master_edition) = collection_master_edition {
-        verify_builder.collection_master_edition(collection_master_edition.key());
-        account_infos.push(collection_master_edition);
-    }
-
-    account_infos = [account_infos, vec![system_program, sysvar_instructions]].concat();
-
-    let verify_ix = verify_builder.build(args).unwrap().instruction();
-
-    if let Some(signer_seeds) = signer_seeds {
-        return solana_program::program::invoke_signed(
-            &verify_ix,
-            &account_infos[..],
-            signer_seeds,
-        )
-        .map_err(Into::into);
-    } else {
-        return solana_program::program::invoke(&verify_ix, &account_infos[..]).map_err(Into::into);
+    VerifyAccounts {
+        authority,
+        delegate_record,
+        metadata,
+        collection_mint,
+        collection_metadata,
+        collection_master_edition,
+        system_program,
+        sysvar_instructions,
     }
+    .invoke(args, signer_seeds)
 }
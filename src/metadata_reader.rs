@@ -0,0 +1,284 @@
+use {
+    crate::{errors::UtilErrors, prelude::*},
+    mpl_token_metadata::state::{
+        Collection, Creator, Key, TokenStandard, UseMethod, Uses, MAX_CREATOR_LEN,
+    },
+};
+
+const UPDATE_AUTHORITY_OFFSET: usize = 1;
+const MINT_OFFSET: usize = 33;
+const NAME_OFFSET: usize = 65;
+
+/// `token_standard`, `collection` and `uses`, which are decoded together.
+type OptionalTail = (Option<TokenStandard>, Option<Collection>, Option<Uses>);
+
+/// Borrow-based view over the raw data of a Token Metadata `Metadata`
+/// account. Fields are decoded on access, so only what is read gets paid for.
+#[derive(Clone, Copy)]
+pub struct MetadataReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> MetadataReader<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        if data.len() < NAME_OFFSET || data[0] != Key::MetadataV1 as u8 {
+            return Err(UtilErrors::InvalidMetadata.into());
+        }
+        Ok(Self { data })
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn update_authority(&self) -> Pubkey {
+        self.pubkey_at(UPDATE_AUTHORITY_OFFSET)
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        self.pubkey_at(MINT_OFFSET)
+    }
+
+    pub fn name(&self) -> Result<&'a str> {
+        self.str_at(NAME_OFFSET)
+    }
+
+    pub fn symbol(&self) -> Result<&'a str> {
+        self.str_at(self.skip_str(NAME_OFFSET)?)
+    }
+
+    pub fn uri(&self) -> Result<&'a str> {
+        self.str_at(self.uri_offset()?)
+    }
+
+    pub fn seller_fee_basis_points(&self) -> Result<u16> {
+        let offset = self.seller_fee_basis_points_offset()?;
+        Ok(u16::from_le_bytes(self.array_at(offset)?))
+    }
+
+    pub fn creators(&self) -> Result<Option<CreatorsReader<'a>>> {
+        let offset = self.creators_offset()?;
+        if !self.option_at(offset)? {
+            return Ok(None);
+        }
+        let count = u32::from_le_bytes(self.array_at(offset + 1)?) as usize;
+        let start = offset + 5;
+        let end = count
+            .checked_mul(MAX_CREATOR_LEN)
+            .and_then(|len| start.checked_add(len))
+            .ok_or(UtilErrors::InvalidMetadata)?;
+        Ok(Some(CreatorsReader {
+            data: self.slice(start, end)?,
+        }))
+    }
+
+    pub fn primary_sale_happened(&self) -> Result<bool> {
+        self.bool_at(self.primary_sale_happened_offset()?)
+    }
+
+    pub fn is_mutable(&self) -> Result<bool> {
+        self.bool_at(self.primary_sale_happened_offset()? + 1)
+    }
+
+    pub fn edition_nonce(&self) -> Result<Option<u8>> {
+        let offset = self.edition_nonce_offset()?;
+        if !self.option_at(offset)? {
+            return Ok(None);
+        }
+        Ok(Some(self.byte_at(offset + 1)?))
+    }
+
+    /// `token_standard`, `collection` and `uses` were appended to `Metadata`
+    /// over time and older accounts may hold padding there, so like Token
+    /// Metadata 1.13.2, if any of the three cannot be decoded, all of them
+    /// read as `None`.
+    pub fn token_standard(&self) -> Result<Option<TokenStandard>> {
+        Ok(self.optional_tail()?.0)
+    }
+
+    pub fn collection(&self) -> Result<Option<Collection>> {
+        Ok(self.optional_tail()?.1)
+    }
+
+    pub fn uses(&self) -> Result<Option<Uses>> {
+        Ok(self.optional_tail()?.2)
+    }
+
+    fn optional_tail(&self) -> Result<OptionalTail> {
+        let offset = self.token_standard_offset()?;
+        Ok(self.read_optional_tail(offset).unwrap_or_default())
+    }
+
+    /// Reads the optional tail at `offset` as strictly as borsh would: it
+    /// fails on truncated data and on out of range tags.
+    fn read_optional_tail(&self, offset: usize) -> Result<OptionalTail> {
+        let (token_standard, offset) = self.tail_option(offset, 1, |offset| {
+            Ok(match self.byte_at(offset)? {
+                0 => TokenStandard::NonFungible,
+                1 => TokenStandard::FungibleAsset,
+                2 => TokenStandard::Fungible,
+                3 => TokenStandard::NonFungibleEdition,
+                4 => TokenStandard::ProgrammableNonFungible,
+                5 => TokenStandard::ProgrammableNonFungibleEdition,
+                _ => return Err(UtilErrors::InvalidMetadata.into()),
+            })
+        })?;
+        let (collection, offset) = self.tail_option(offset, 33, |offset| {
+            let verified = match self.byte_at(offset)? {
+                0 => false,
+                1 => true,
+                _ => return Err(UtilErrors::InvalidMetadata.into()),
+            };
+            Ok(Collection {
+                verified,
+                key: Pubkey::new_from_array(self.array_at(offset + 1)?),
+            })
+        })?;
+        let (uses, _) = self.tail_option(offset, 17, |offset| {
+            let use_method = match self.byte_at(offset)? {
+                0 => UseMethod::Burn,
+                1 => UseMethod::Multiple,
+                2 => UseMethod::Single,
+                _ => return Err(UtilErrors::InvalidMetadata.into()),
+            };
+            Ok(Uses {
+                use_method,
+                remaining: u64::from_le_bytes(self.array_at(offset + 1)?),
+                total: u64::from_le_bytes(self.array_at(offset + 9)?),
+            })
+        })?;
+        Ok((token_standard, collection, uses))
+    }
+
+    /// Reads the `Option` at `offset` with `read`, given the offset of its
+    /// value, and returns it with the offset past it. Unlike `option_at`, a
+    /// missing tag is an error.
+    fn tail_option<T>(
+        &self,
+        offset: usize,
+        len: usize,
+        read: impl FnOnce(usize) -> Result<T>,
+    ) -> Result<(Option<T>, usize)> {
+        match self.byte_at(offset)? {
+            0 => Ok((None, offset + 1)),
+            1 => Ok((Some(read(offset + 1)?), offset + 1 + len)),
+            _ => Err(UtilErrors::InvalidMetadata.into()),
+        }
+    }
+
+    fn uri_offset(&self) -> Result<usize> {
+        self.skip_str(self.skip_str(NAME_OFFSET)?)
+    }
+
+    fn seller_fee_basis_points_offset(&self) -> Result<usize> {
+        self.skip_str(self.uri_offset()?)
+    }
+
+    fn creators_offset(&self) -> Result<usize> {
+        Ok(self.seller_fee_basis_points_offset()? + 2)
+    }
+
+    fn primary_sale_happened_offset(&self) -> Result<usize> {
+        let offset = self.creators_offset()?;
+        if !self.option_at(offset)? {
+            return Ok(offset + 1);
+        }
+        let count = u32::from_le_bytes(self.array_at(offset + 1)?) as usize;
+        count
+            .checked_mul(MAX_CREATOR_LEN)
+            .and_then(|len| len.checked_add(offset + 5))
+            .ok_or_else(|| UtilErrors::InvalidMetadata.into())
+    }
+
+    fn edition_nonce_offset(&self) -> Result<usize> {
+        Ok(self.primary_sale_happened_offset()? + 2)
+    }
+
+    fn token_standard_offset(&self) -> Result<usize> {
+        self.skip_option(self.edition_nonce_offset()?, 1)
+    }
+
+    fn pubkey_at(&self, offset: usize) -> Pubkey {
+        Pubkey::new_from_array(self.data[offset..offset + 32].try_into().unwrap())
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Result<&'a [u8]> {
+        self.data
+            .get(start..end)
+            .ok_or_else(|| UtilErrors::InvalidMetadata.into())
+    }
+
+    fn array_at<const N: usize>(&self, offset: usize) -> Result<[u8; N]> {
+        Ok(self.slice(offset, offset + N)?.try_into().unwrap())
+    }
+
+    fn byte_at(&self, offset: usize) -> Result<u8> {
+        self.data
+            .get(offset)
+            .copied()
+            .ok_or_else(|| UtilErrors::InvalidMetadata.into())
+    }
+
+    fn bool_at(&self, offset: usize) -> Result<bool> {
+        Ok(self.byte_at(offset)? != 0)
+    }
+
+    /// Accounts may end before the trailing optional fields, which then
+    /// read as `None`.
+    fn option_at(&self, offset: usize) -> Result<bool> {
+        match self.data.get(offset) {
+            None | Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            Some(_) => Err(UtilErrors::InvalidMetadata.into()),
+        }
+    }
+
+    fn skip_option(&self, offset: usize, len: usize) -> Result<usize> {
+        if self.option_at(offset)? {
+            Ok(offset + 1 + len)
+        } else {
+            Ok(offset + 1)
+        }
+    }
+
+    fn str_at(&self, offset: usize) -> Result<&'a str> {
+        let len = u32::from_le_bytes(self.array_at(offset)?) as usize;
+        let bytes = self.slice(offset + 4, offset + 4 + len)?;
+        std::str::from_utf8(bytes).map_err(|_| UtilErrors::InvalidMetadata.into())
+    }
+
+    fn skip_str(&self, offset: usize) -> Result<usize> {
+        let len = u32::from_le_bytes(self.array_at(offset)?) as usize;
+        Ok(offset + 4 + len)
+    }
+}
+
+/// Borrow-based view over the creators list of a `Metadata` account.
+#[derive(Clone, Copy)]
+pub struct CreatorsReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> CreatorsReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len() / MAX_CREATOR_LEN
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Creator> + 'a {
+        self.data
+            .chunks_exact(MAX_CREATOR_LEN)
+            .map(|creator| Creator {
+                address: Pubkey::new_from_array(creator[..32].try_into().unwrap()),
+                verified: creator[32] != 0,
+                share: creator[33],
+            })
+    }
+}
@@ -0,0 +1,121 @@
+use {
+    crate::{errors::UtilErrors, prelude::*},
+    mpl_token_metadata::{
+        instruction::MetadataDelegateRole,
+        pda::{
+            find_collection_authority_account, find_edition_account, find_master_edition_account,
+            find_metadata_account, find_metadata_delegate_record_account,
+            find_token_record_account, find_use_authority_account,
+        },
+        state::EDITION_MARKER_BIT_SIZE,
+    },
+};
+
+pub fn find_metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    find_metadata_account(mint)
+}
+
+/// Master editions and printed editions share the same derivation.
+pub fn find_edition(mint: &Pubkey) -> (Pubkey, u8) {
+    find_master_edition_account(mint)
+}
+
+pub fn find_edition_marker(master_edition_mint: &Pubkey, edition: u64) -> (Pubkey, u8) {
+    find_edition_account(
+        master_edition_mint,
+        (edition / EDITION_MARKER_BIT_SIZE).to_string(),
+    )
+}
+
+pub fn find_token_record(mint: &Pubkey, token: &Pubkey) -> (Pubkey, u8) {
+    find_token_record_account(mint, token)
+}
+
+pub fn find_delegate_record(
+    mint: &Pubkey,
+    role: MetadataDelegateRole,
+    update_authority: &Pubkey,
+    delegate: &Pubkey,
+) -> (Pubkey, u8) {
+    find_metadata_delegate_record_account(mint, role, update_authority, delegate)
+}
+
+pub fn find_collection_authority_record(mint: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    find_collection_authority_account(mint, authority)
+}
+
+pub fn find_use_authority_record(mint: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    find_use_authority_account(mint, authority)
+}
+
+/// Checks that `account` lives at `expected` and returns the bump, logging
+/// both addresses under `name` when they differ.
+pub fn assert_pda(name: &str, account: &AccountInfo, (expected, bump): (Pubkey, u8)) -> Result<u8> {
+    if account.key() != expected {
+        msg!("{}: expected {}, got {}", name, expected, account.key());
+        return Err(UtilErrors::InvalidPda.into());
+    }
+    Ok(bump)
+}
+
+pub fn assert_metadata(account: &AccountInfo, mint: &Pubkey) -> Result<u8> {
+    assert_pda("metadata", account, find_metadata(mint))
+}
+
+pub fn assert_edition(account: &AccountInfo, mint: &Pubkey) -> Result<u8> {
+    assert_pda("edition", account, find_edition(mint))
+}
+
+pub fn assert_edition_marker(
+    account: &AccountInfo,
+    master_edition_mint: &Pubkey,
+    edition: u64,
+) -> Result<u8> {
+    assert_pda(
+        "edition_marker",
+        account,
+        find_edition_marker(master_edition_mint, edition),
+    )
+}
+
+pub fn assert_token_record(account: &AccountInfo, mint: &Pubkey, token: &Pubkey) -> Result<u8> {
+    assert_pda("token_record", account, find_token_record(mint, token))
+}
+
+pub fn assert_delegate_record(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    role: MetadataDelegateRole,
+    update_authority: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<u8> {
+    assert_pda(
+        "delegate_record",
+        account,
+        find_delegate_record(mint, role, update_authority, delegate),
+    )
+}
+
+pub fn assert_collection_authority_record(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    authority: &Pubkey,
+) -> Result<u8> {
+    assert_pda(
+        "collection_authority_record",
+        account,
+        find_collection_authority_record(mint, authority),
+    )
+}
+
+pub fn assert_use_authority_record(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    authority: &Pubkey,
+) -> Result<u8> {
+    assert_pda(
+        "use_authority_record",
+        account,
+        find_use_authority_record(mint, authority),
+    )
+}
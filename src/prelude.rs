@@ -0,0 +1,61 @@
+//! The account, error and serialization types the crate is written against.
+//!
+//! With the default `anchor` feature these are Anchor's own. Without it they
+//! are plain `solana_program` types, so native programs can use the crate
+//! without pulling Anchor in: `Result<()>` is then `ProgramResult`.
+
+#[cfg(feature = "anchor")]
+pub use anchor_lang::prelude::*;
+
+#[cfg(not(feature = "anchor"))]
+pub use {
+    borsh::{BorshDeserialize as AnchorDeserialize, BorshSerialize as AnchorSerialize},
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, instruction::AccountMeta, msg,
+        program_error::ProgramError, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
+    },
+};
+
+/// The rent sysvar account: Anchor's `Sysvar<Rent>`, or `Rent` itself.
+#[cfg(feature = "anchor")]
+pub type RentSysvar<'info> = Sysvar<'info, Rent>;
+
+/// The rent sysvar account: Anchor's `Sysvar<Rent>`, or `Rent` itself.
+#[cfg(not(feature = "anchor"))]
+pub type RentSysvar<'info> = Rent;
+
+/// The system program account: Anchor's `Program<System>`, or its
+/// `AccountInfo`.
+#[cfg(feature = "anchor")]
+pub type SystemProgram<'info> = Program<'info, System>;
+
+/// The system program account: Anchor's `Program<System>`, or its
+/// `AccountInfo`.
+#[cfg(not(feature = "anchor"))]
+pub type SystemProgram<'info> = AccountInfo<'info>;
+
+#[cfg(not(feature = "anchor"))]
+pub type Error = ProgramError;
+
+#[cfg(not(feature = "anchor"))]
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Stand-in for Anchor's `Key` trait.
+#[cfg(not(feature = "anchor"))]
+pub trait Key {
+    fn key(&self) -> Pubkey;
+}
+
+#[cfg(not(feature = "anchor"))]
+impl Key for AccountInfo<'_> {
+    fn key(&self) -> Pubkey {
+        *self.key
+    }
+}
+
+#[cfg(not(feature = "anchor"))]
+impl Key for Pubkey {
+    fn key(&self) -> Pubkey {
+        *self
+    }
+}
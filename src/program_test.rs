@@ -0,0 +1,258 @@
+//! A `solana-program-test` harness running the wrappers against the real
+//! Token Metadata program.
+//!
+//! Token Metadata runs natively from the `mpl-token-metadata` 1.13.2 crate
+//! this crate is pinned to, so the suite needs no binary. A
+//! `mpl_token_metadata.so` found in `tests/fixtures`, `BPF_OUT_DIR` or
+//! `SBF_OUT_DIR`, as `ProgramTest` searches them, is loaded instead, for
+//! runs against a deployed build of the same version.
+
+use {
+    crate::prelude::*,
+    solana_program::{
+        entrypoint::ProgramResult,
+        instruction::Instruction,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+        system_program,
+    },
+    solana_program_test::{
+        find_file, processor, BanksClientError, ProgramTest, ProgramTestContext,
+    },
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+    std::{
+        path::PathBuf,
+        sync::{Once, OnceLock, RwLock},
+    },
+};
+
+pub const TOKEN_METADATA_PROGRAM_NAME: &str = "mpl_token_metadata";
+
+/// Compute limit of every transaction, enough for pNFT instructions made
+/// through CPI.
+pub const COMPUTE_MAX_UNITS: u64 = 1_400_000;
+
+/// The Token Metadata binary `ProgramTest` will load, if there is one.
+pub fn token_metadata_binary() -> Option<PathBuf> {
+    find_file(&format!("{TOKEN_METADATA_PROGRAM_NAME}.so"))
+}
+
+/// Adds Token Metadata to `program_test` and starts it.
+///
+/// Programs under test should be added natively with `processor!` before
+/// calling this.
+pub async fn start_with_token_metadata(mut program_test: ProgramTest) -> ProgramTestContext {
+    program_test.prefer_bpf(token_metadata_binary().is_some());
+    program_test.add_program(
+        TOKEN_METADATA_PROGRAM_NAME,
+        mpl_token_metadata::ID,
+        processor!(process_token_metadata),
+    );
+    program_test.set_compute_max_units(COMPUTE_MAX_UNITS);
+    let mut context = program_test.start_with_context().await;
+
+    install_program_account_stubs();
+    // Token Metadata itself leaves the programs it invokes out of its CPIs.
+    for program_id in [mpl_token_metadata::ID, system_program::ID, spl_token::ID] {
+        let account = context
+            .banks_client
+            .get_account(program_id)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("{program_id} is loaded"));
+        register_program_account(program_id, account);
+    }
+    context
+}
+
+/// Sends `instructions` in one transaction paid by the context payer, which
+/// signs along with `signers`.
+pub async fn process_instructions(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    // A fresh blockhash keeps repeated, identical transactions distinct.
+    let blockhash = context.get_new_latest_blockhash().await?;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// Token Metadata's processor ties the lifetime of the account infos to the
+/// borrow of their slice, which `processor!` cannot express.
+///
+/// Token Metadata also serializes some records through `&mut &mut [u8]`,
+/// which moves the account's data slice past the bytes written. The BPF
+/// loader ignores the slice, but the native harness stores whatever it points
+/// at, so slices moved this way are put back before returning.
+fn process_token_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // SAFETY: the account infos and the slice holding them both outlive the
+    // call, and Token Metadata keeps no reference to either past it.
+    let accounts: &[AccountInfo] = unsafe { std::mem::transmute(accounts) };
+    let slices: Vec<_> = accounts
+        .iter()
+        .map(|account| {
+            let data = account.data.borrow();
+            (data.as_ptr(), data.len())
+        })
+        .collect();
+    let result = mpl_token_metadata::processor::process_instruction(program_id, accounts, data);
+    for (account, (ptr, len)) in accounts.iter().zip(slices) {
+        let mut data = account.data.borrow_mut();
+        if data.as_ptr() != ptr {
+            // SAFETY: the slice is the one the account info was created with,
+            // and the account's buffer is still alive.
+            *data = unsafe { std::slice::from_raw_parts_mut(ptr as *mut u8, len) };
+        }
+    }
+    result
+}
+
+static PROGRAM_TEST_STUBS: OnceLock<Box<dyn SyscallStubs>> = OnceLock::new();
+static PROGRAM_ACCOUNTS: RwLock<Vec<(Pubkey, Account)>> = RwLock::new(Vec::new());
+
+fn program_test_stubs() -> &'static dyn SyscallStubs {
+    PROGRAM_TEST_STUBS
+        .get()
+        .expect("program-test stubs are installed")
+        .as_ref()
+}
+
+fn register_program_account(program_id: Pubkey, account: Account) {
+    let mut accounts = PROGRAM_ACCOUNTS.write().unwrap();
+    accounts.retain(|(key, _)| *key != program_id);
+    accounts.push((program_id, account));
+}
+
+/// Wraps the stubs `ProgramTest` installs for natively run programs.
+///
+/// Unlike the runtime, those stubs want an account info for every
+/// instruction account, executable ones included, so a CPI passing a program
+/// id in place of an omitted optional account, or leaving out the program
+/// accounts it uses, fails there only. This fills
+/// in the registered program accounts, unchanged, before delegating.
+fn install_program_account_stubs() {
+    static INSTALL: Once = Once::new();
+    // `ProgramTest` installs its own stubs once, when the first bank is set
+    // up, and no transaction can run before this returns.
+    INSTALL.call_once(|| {
+        let program_test_stubs = set_syscall_stubs(Box::new(ProgramAccountStubs));
+        assert!(PROGRAM_TEST_STUBS.set(program_test_stubs).is_ok());
+    });
+}
+
+struct ProgramAccountStubs;
+
+impl SyscallStubs for ProgramAccountStubs {
+    fn sol_log(&self, message: &str) {
+        program_test_stubs().sol_log(message)
+    }
+
+    fn sol_log_compute_units(&self) {
+        program_test_stubs().sol_log_compute_units()
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let mut missing: Vec<(Pubkey, Account)> = PROGRAM_ACCOUNTS
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| {
+                instruction.accounts.iter().any(|meta| meta.pubkey == *key)
+                    && !account_infos.iter().any(|info| info.key == key)
+            })
+            .cloned()
+            .collect();
+
+        let mut all_infos = account_infos.to_vec();
+        all_infos.extend(missing.iter_mut().map(|(key, account)| {
+            // SAFETY: these account infos only live in `all_infos`, which is
+            // dropped before `missing`, and the stubs keep none past the call.
+            unsafe {
+                AccountInfo::new(
+                    &*(key as *const Pubkey),
+                    false,
+                    false,
+                    &mut *(&mut account.lamports as *mut u64),
+                    &mut *(account.data.as_mut_slice() as *mut [u8]),
+                    &*(&account.owner as *const Pubkey),
+                    account.executable,
+                    account.rent_epoch,
+                )
+            }
+        }));
+        program_test_stubs().sol_invoke_signed(instruction, &all_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        program_test_stubs().sol_get_clock_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        program_test_stubs().sol_get_epoch_schedule_sysvar(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        program_test_stubs().sol_get_fees_sysvar(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        program_test_stubs().sol_get_rent_sysvar(var_addr)
+    }
+
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        program_test_stubs().sol_memcpy(dst, src, n)
+    }
+
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        program_test_stubs().sol_memmove(dst, src, n)
+    }
+
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        program_test_stubs().sol_memcmp(s1, s2, n, result)
+    }
+
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        program_test_stubs().sol_memset(s, c, n)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        program_test_stubs().sol_get_return_data()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        program_test_stubs().sol_set_return_data(data)
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        program_test_stubs().sol_log_data(fields)
+    }
+
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        program_test_stubs().sol_get_processed_sibling_instruction(index)
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        program_test_stubs().sol_get_stack_height()
+    }
+}
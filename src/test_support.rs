@@ -0,0 +1,132 @@
+//! Host-side support for unit-testing the CPI wrappers without a validator.
+//!
+//! [`record_cpis`] installs `solana_program` syscall stubs that capture every
+//! `invoke` and `invoke_signed` instead of executing them, so tests can assert
+//! the instruction, account flags and signer seeds a wrapper produces.
+
+use {
+    crate::{errors::UtilErrors, prelude::*},
+    solana_program::{
+        entrypoint::ProgramResult,
+        instruction::Instruction,
+        program_error::ProgramError,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+    },
+    std::{cell::RefCell, sync::Once},
+};
+
+/// A cross-program invocation captured by [`record_cpis`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedCpi {
+    pub instruction: Instruction,
+    /// The account infos handed to `invoke`, as passed, with their own
+    /// signer and writable flags.
+    pub account_infos: Vec<AccountMeta>,
+    pub signer_seeds: Vec<Vec<Vec<u8>>>,
+}
+
+impl RecordedCpi {
+    pub fn program_id(&self) -> Pubkey {
+        self.instruction.program_id
+    }
+
+    /// Keys of the instruction accounts, in instruction order.
+    pub fn account_keys(&self) -> Vec<Pubkey> {
+        self.instruction
+            .accounts
+            .iter()
+            .map(|meta| meta.pubkey)
+            .collect()
+    }
+}
+
+thread_local! {
+    static RECORDED: RefCell<Option<Vec<RecordedCpi>>> = const { RefCell::new(None) };
+}
+
+struct RecordingStubs;
+
+impl SyscallStubs for RecordingStubs {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let recorded = RecordedCpi {
+            instruction: instruction.clone(),
+            account_infos: account_infos
+                .iter()
+                .map(|info| AccountMeta {
+                    pubkey: *info.key,
+                    is_signer: info.is_signer,
+                    is_writable: info.is_writable,
+                })
+                .collect(),
+            signer_seeds: signers_seeds
+                .iter()
+                .map(|seeds| seeds.iter().map(|seed| seed.to_vec()).collect())
+                .collect(),
+        };
+
+        // Executable accounts need no account info, which covers the program
+        // id standing in for omitted optional accounts.
+        let missing = instruction.accounts.iter().find(|meta| {
+            meta.pubkey != instruction.program_id
+                && !account_infos.iter().any(|info| *info.key == meta.pubkey)
+        });
+
+        RECORDED.with(|recorded_cpis| match recorded_cpis.borrow_mut().as_mut() {
+            Some(recorded_cpis) => recorded_cpis.push(recorded),
+            None => panic!("CPI made outside of record_cpis"),
+        });
+
+        // The runtime rejects instructions whose accounts were not passed in.
+        match missing {
+            Some(meta) => {
+                msg!("Account {} is missing from the account infos", meta.pubkey);
+                Err(ProgramError::NotEnoughAccountKeys)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Runs `f`, capturing the CPIs it makes on the current thread instead of
+/// executing them. Every captured call succeeds unless one of its
+/// instruction accounts, other than the invoked program, is missing from the
+/// account infos.
+///
+/// The stubs are installed process-wide on first use and record per thread,
+/// so tests using this can run in parallel.
+pub fn record_cpis<T>(f: impl FnOnce() -> T) -> (T, Vec<RecordedCpi>) {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(RecordingStubs));
+    });
+
+    RECORDED.with(|recorded| *recorded.borrow_mut() = Some(vec![]));
+    let output = f();
+    let recorded = RECORDED.with(|recorded| recorded.borrow_mut().take().unwrap_or_default());
+    (output, recorded)
+}
+
+/// The `ProgramError` a wrapper's error reaches the runtime as, for comparing
+/// errors the same way with and without Anchor.
+#[cfg(feature = "anchor")]
+pub fn program_error(error: Error) -> ProgramError {
+    error.into()
+}
+
+#[cfg(not(feature = "anchor"))]
+pub fn program_error(error: Error) -> ProgramError {
+    error
+}
+
+/// Asserts that `result` failed with `expected`.
+pub fn assert_error<T: std::fmt::Debug>(result: Result<T>, expected: UtilErrors) {
+    assert_eq!(
+        program_error(result.unwrap_err()),
+        program_error(expected.into())
+    );
+}
@@ -0,0 +1,75 @@
+use {
+    crate::{errors::UtilErrors, prelude::*},
+    spl_token_2022::{
+        extension::{
+            non_transferable::NonTransferable, permanent_delegate::PermanentDelegate,
+            transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Mint,
+    },
+};
+
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::ID || *program_id == spl_token_2022::ID
+}
+
+/// Checks that `token_program` is SPL Token or Token-2022 and owns `mint`.
+pub fn assert_token_program(mint: &AccountInfo, token_program: &AccountInfo) -> Result<()> {
+    if !is_token_program(token_program.key) || mint.owner != token_program.key {
+        msg!(
+            "Token program {} does not own mint {}",
+            token_program.key,
+            mint.key
+        );
+        return Err(UtilErrors::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// The Token-2022 mint extensions that change how an NFT can move.
+/// Classic SPL Token mints have none of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MintExtensions {
+    pub non_transferable: bool,
+    pub permanent_delegate: Option<Pubkey>,
+    pub transfer_hook_program: Option<Pubkey>,
+    pub transfer_hook_authority: Option<Pubkey>,
+}
+
+impl MintExtensions {
+    pub fn read(mint: &AccountInfo) -> Result<Self> {
+        if *mint.owner == spl_token::ID {
+            return Ok(Self::default());
+        }
+        if *mint.owner != spl_token_2022::ID {
+            return Err(UtilErrors::InvalidTokenProgram.into());
+        }
+
+        let data = mint.try_borrow_data()?;
+        let state = StateWithExtensions::<Mint>::unpack(&data)?;
+        let transfer_hook = state.get_extension::<TransferHook>().ok();
+        Ok(Self {
+            non_transferable: state.get_extension::<NonTransferable>().is_ok(),
+            permanent_delegate: state
+                .get_extension::<PermanentDelegate>()
+                .ok()
+                .and_then(|extension| Option::<Pubkey>::from(extension.delegate)),
+            transfer_hook_program: transfer_hook
+                .and_then(|extension| Option::<Pubkey>::from(extension.program_id)),
+            transfer_hook_authority: transfer_hook
+                .and_then(|extension| Option::<Pubkey>::from(extension.authority)),
+        })
+    }
+}
+
+pub fn is_non_transferable(mint: &AccountInfo) -> Result<bool> {
+    Ok(MintExtensions::read(mint)?.non_transferable)
+}
+
+pub fn permanent_delegate(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    Ok(MintExtensions::read(mint)?.permanent_delegate)
+}
+
+pub fn transfer_hook_program(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    Ok(MintExtensions::read(mint)?.transfer_hook_program)
+}
@@ -0,0 +1,283 @@
+use {
+    crate::{
+        errors::UtilErrors,
+        merkle_tree::{hash_leaf, verify_merkle_slice, MerkleVersion},
+        metadata_reader::MetadataReader,
+        prelude::*,
+    },
+    solana_program::keccak::hashv,
+};
+
+/// What gets looked up in a merkle allowlist.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AllowlistLeaf {
+    /// The mint address bytes.
+    Mint,
+    /// The keccak hash of the raw metadata account data. That is the whole
+    /// account, padding included, so any update to the metadata, even one
+    /// leaving the fields an allowlist cares about untouched, changes the
+    /// leaf and invalidates the entry.
+    MetadataHash,
+}
+
+impl AllowlistLeaf {
+    /// Returns the leaf of the NFT encoded for `version`.
+    pub fn leaf(&self, metadata: &MetadataReader, version: MerkleVersion) -> [u8; 32] {
+        let value = match self {
+            Self::Mint => metadata.mint().to_bytes(),
+            Self::MetadataHash => hashv(&[metadata.data()]).0,
+        };
+        match version {
+            MerkleVersion::V1 => value,
+            MerkleVersion::V2 => hash_leaf(&[&value]).0,
+        }
+    }
+}
+
+/// A composable rule an NFT's metadata has to satisfy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationPolicy {
+    /// The verified collection is one of the listed keys.
+    Collection(Vec<Pubkey>),
+    /// At least one verified creator is one of the listed keys.
+    Creator(Vec<Pubkey>),
+    /// At least one verified creator is one of the listed keys and holds at
+    /// least `min_share` percent of the royalties.
+    CreatorWithShare {
+        creators: Vec<Pubkey>,
+        min_share: u8,
+    },
+    /// Every listed key is a verified creator. Fails when no key is listed.
+    AllCreatorsVerified(Vec<Pubkey>),
+    /// The update authority is one of the listed keys.
+    UpdateAuthority(Vec<Pubkey>),
+    /// The `leaf` of the NFT is proved by `proof` to be part of the merkle
+    /// allowlist defined by `root`.
+    MerkleAllowlist {
+        root: [u8; 32],
+        proof: Vec<[u8; 32]>,
+        leaf: AllowlistLeaf,
+        version: MerkleVersion,
+    },
+    /// Every listed policy passes. Fails when no policy is listed.
+    All(Vec<ValidationPolicy>),
+    /// At least one listed policy passes.
+    Any(Vec<ValidationPolicy>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyMatch {
+    Collection { address: Pubkey },
+    Creator { address: Pubkey, share: u8 },
+    UpdateAuthority { address: Pubkey },
+    MerkleAllowlist { leaf: [u8; 32] },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyFailure {
+    Collection,
+    Creator,
+    CreatorShare {
+        min_share: u8,
+    },
+    UnverifiedCreator {
+        address: Pubkey,
+    },
+    UpdateAuthority,
+    MerkleAllowlist,
+    /// An `All` or `AllCreatorsVerified` policy without anything to check,
+    /// which would otherwise pass any NFT.
+    EmptyPolicy,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub passed: bool,
+    pub matches: Vec<PolicyMatch>,
+    pub failures: Vec<PolicyFailure>,
+}
+
+impl ValidationReport {
+    fn matched(matches: Vec<PolicyMatch>) -> Self {
+        Self {
+            passed: true,
+            matches,
+            failures: vec![],
+        }
+    }
+
+    fn failed(failures: Vec<PolicyFailure>) -> Self {
+        Self {
+            passed: false,
+            matches: vec![],
+            failures,
+        }
+    }
+}
+
+impl ValidationPolicy {
+    /// Evaluates the policy against `metadata`, only failing when the
+    /// metadata itself cannot be read.
+    pub fn evaluate(&self, metadata: &MetadataReader) -> Result<ValidationReport> {
+        match self {
+            Self::Collection(collections) => Ok(match metadata.collection()? {
+                Some(collection)
+                    if collection.verified && collections.contains(&collection.key) =>
+                {
+                    ValidationReport::matched(vec![PolicyMatch::Collection {
+                        address: collection.key,
+                    }])
+                }
+                _ => ValidationReport::failed(vec![PolicyFailure::Collection]),
+            }),
+            Self::Creator(creators) => {
+                Self::evaluate_creators(metadata, creators, 0).map(|report| {
+                    report.unwrap_or_else(|| ValidationReport::failed(vec![PolicyFailure::Creator]))
+                })
+            }
+            Self::CreatorWithShare {
+                creators,
+                min_share,
+            } => Self::evaluate_creators(metadata, creators, *min_share).map(|report| {
+                report.unwrap_or_else(|| {
+                    ValidationReport::failed(vec![PolicyFailure::CreatorShare {
+                        min_share: *min_share,
+                    }])
+                })
+            }),
+            Self::AllCreatorsVerified(creators) if creators.is_empty() => {
+                Ok(ValidationReport::failed(vec![PolicyFailure::EmptyPolicy]))
+            }
+            Self::AllCreatorsVerified(creators) => {
+                let verified = metadata
+                    .creators()?
+                    .map(|list| list.iter().filter(|x| x.verified).collect::<Vec<_>>())
+                    .unwrap_or_default();
+
+                let mut matches = vec![];
+                let mut failures = vec![];
+                for address in creators {
+                    match verified.iter().find(|x| x.address == *address) {
+                        Some(creator) => matches.push(PolicyMatch::Creator {
+                            address: creator.address,
+                            share: creator.share,
+                        }),
+                        None => {
+                            failures.push(PolicyFailure::UnverifiedCreator { address: *address })
+                        }
+                    }
+                }
+
+                if failures.is_empty() {
+                    Ok(ValidationReport::matched(matches))
+                } else {
+                    Ok(ValidationReport::failed(failures))
+                }
+            }
+            Self::UpdateAuthority(authorities) => {
+                let update_authority = metadata.update_authority();
+                if authorities.contains(&update_authority) {
+                    Ok(ValidationReport::matched(vec![
+                        PolicyMatch::UpdateAuthority {
+                            address: update_authority,
+                        },
+                    ]))
+                } else {
+                    Ok(ValidationReport::failed(vec![
+                        PolicyFailure::UpdateAuthority,
+                    ]))
+                }
+            }
+            Self::MerkleAllowlist {
+                root,
+                proof,
+                leaf,
+                version,
+            } => {
+                let leaf = leaf.leaf(metadata, *version);
+                if verify_merkle_slice(proof, *root, leaf) {
+                    Ok(ValidationReport::matched(vec![
+                        PolicyMatch::MerkleAllowlist { leaf },
+                    ]))
+                } else {
+                    Ok(ValidationReport::failed(vec![
+                        PolicyFailure::MerkleAllowlist,
+                    ]))
+                }
+            }
+            Self::All(policies) if policies.is_empty() => {
+                Ok(ValidationReport::failed(vec![PolicyFailure::EmptyPolicy]))
+            }
+            Self::All(policies) => {
+                let mut report = ValidationReport::matched(vec![]);
+                for policy in policies {
+                    let child = policy.evaluate(metadata)?;
+                    report.passed &= child.passed;
+                    report.matches.extend(child.matches);
+                    report.failures.extend(child.failures);
+                }
+                if !report.passed {
+                    report.matches.clear();
+                }
+                Ok(report)
+            }
+            Self::Any(policies) => {
+                let mut failures = vec![];
+                for policy in policies {
+                    let child = policy.evaluate(metadata)?;
+                    if child.passed {
+                        return Ok(child);
+                    }
+                    failures.extend(child.failures);
+                }
+                Ok(ValidationReport::failed(failures))
+            }
+        }
+    }
+
+    /// Same as [`ValidationPolicy::evaluate`] but fails with
+    /// `UtilErrors::InvalidNFT` after logging what did not match.
+    pub fn validate(&self, metadata: &MetadataReader) -> Result<ValidationReport> {
+        let report = self.evaluate(metadata)?;
+        if !report.passed {
+            msg!("NFT validation failed: {:?}", report.failures);
+            return Err(UtilErrors::InvalidNFT.into());
+        }
+        Ok(report)
+    }
+
+    fn evaluate_creators(
+        metadata: &MetadataReader,
+        creators: &[Pubkey],
+        min_share: u8,
+    ) -> Result<Option<ValidationReport>> {
+        let Some(metadata_creators) = metadata.creators()? else {
+            return Ok(None);
+        };
+        Ok(metadata_creators
+            .iter()
+            .find(|x| x.verified && x.share >= min_share && creators.contains(&x.address))
+            .map(|creator| {
+                ValidationReport::matched(vec![PolicyMatch::Creator {
+                    address: creator.address,
+                    share: creator.share,
+                }])
+            }))
+    }
+}
+
+/// Checks that the NFT behind `metadata` is part of the merkle allowlist
+/// defined by `root`, returning the proved leaf.
+pub fn validate_merkle_allowlist(
+    metadata: &MetadataReader,
+    root: [u8; 32],
+    proof: &[[u8; 32]],
+    leaf: AllowlistLeaf,
+    version: MerkleVersion,
+) -> Result<[u8; 32]> {
+    let leaf = leaf.leaf(metadata, version);
+    if !verify_merkle_slice(proof, root, leaf) {
+        return Err(UtilErrors::InvalidNFT.into());
+    }
+    Ok(leaf)
+}
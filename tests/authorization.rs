@@ -0,0 +1,62 @@
+use hpl_utils::{
+    authorization::{
+        amount_entry, authorization_data, merkle_proof_entry, pubkey_entry, seeds_entry,
+        AuthorizationData, PayloadKey, PayloadType, ProofInfo, SeedsVec,
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+#[test]
+fn entries_are_keyed_by_their_payload_key() {
+    let destination = Pubkey::new_unique();
+    let data = authorization_data([
+        pubkey_entry(PayloadKey::Destination, destination),
+        seeds_entry(PayloadKey::DestinationSeeds, vec![b"seed".to_vec()]),
+        merkle_proof_entry(PayloadKey::Source, vec![[1; 32], [2; 32]]),
+        amount_entry(5),
+    ]);
+
+    let payload = &data.payload;
+    assert_eq!(
+        payload.get_pubkey(&"Destination".to_string()),
+        Some(&destination)
+    );
+    assert_eq!(
+        payload.get_seeds(&"DestinationSeeds".to_string()),
+        Some(&SeedsVec::new(vec![b"seed".to_vec()]))
+    );
+    assert_eq!(
+        payload.get_merkle_proof(&"Source".to_string()),
+        Some(&ProofInfo::new(vec![[1; 32], [2; 32]]))
+    );
+    assert_eq!(payload.get_amount(&"Amount".to_string()), Some(5));
+}
+
+#[test]
+fn amount_entry_uses_the_amount_key() {
+    assert_eq!(
+        amount_entry(7),
+        (PayloadKey::Amount, PayloadType::Number(7))
+    );
+}
+
+#[test]
+fn later_entries_replace_earlier_ones_with_the_same_key() {
+    let authority = Pubkey::new_unique();
+    let data = authorization_data([
+        pubkey_entry(PayloadKey::Authority, Pubkey::new_unique()),
+        pubkey_entry(PayloadKey::Authority, authority),
+    ]);
+
+    assert_eq!(
+        data.payload.get(&"Authority".to_string()),
+        Some(&PayloadType::Pubkey(authority))
+    );
+}
+
+#[test]
+fn no_entries_build_an_empty_payload() {
+    let data = authorization_data([]);
+
+    assert_eq!(data, AuthorizationData::new_empty());
+}
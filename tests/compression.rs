@@ -0,0 +1,415 @@
+use {
+    borsh::BorshSerialize,
+    hpl_utils::{
+        bubblegum::{
+            find_tree_config, hash_creators, hash_metadata, CompressedNft, LeafSchema,
+            MetadataArgs, MetadataArgsReader,
+        },
+        compression::{empty_node, ConcurrentMerkleTreeReader, SPL_ACCOUNT_COMPRESSION_ID},
+        fixtures::{AccountBuilder, TestAccount},
+        mpl_bubblegum::{
+            self,
+            types::{Collection, Creator, TokenProgramVersion, TokenStandard, UseMethod, Uses},
+        },
+        solana_program::{keccak::hashv, pubkey::Pubkey},
+    },
+    spl_concurrent_merkle_tree::concurrent_merkle_tree::ConcurrentMerkleTree,
+};
+
+const MAX_DEPTH: usize = 5;
+const MAX_BUFFER_SIZE: usize = 8;
+
+/// A concurrent merkle tree updated by the account compression library
+/// itself, along with every leaf to compute proofs and the canopy from.
+struct Tree {
+    cmt: ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>,
+    leaves: Vec<[u8; 32]>,
+    len: usize,
+}
+
+impl Tree {
+    fn new() -> Self {
+        let mut cmt = ConcurrentMerkleTree::new();
+        cmt.initialize().unwrap();
+        Self {
+            cmt,
+            leaves: vec![[0; 32]; 1 << MAX_DEPTH],
+            len: 0,
+        }
+    }
+
+    fn with_leaves(count: usize) -> Self {
+        let mut tree = Self::new();
+        for i in 0..count {
+            tree.append(leaf(i));
+        }
+        tree
+    }
+
+    fn append(&mut self, leaf: [u8; 32]) {
+        self.cmt.append(leaf).unwrap();
+        self.leaves[self.len] = leaf;
+        self.len += 1;
+    }
+
+    fn set_leaf(&mut self, index: usize, leaf: [u8; 32]) {
+        let proof = self.proof(index);
+        self.cmt
+            .set_leaf(self.root(), self.leaves[index], leaf, &proof, index as u32)
+            .unwrap();
+        self.leaves[index] = leaf;
+    }
+
+    fn root(&self) -> [u8; 32] {
+        self.cmt.get_root()
+    }
+
+    /// Every level of the tree, from the leaves up to the root.
+    fn levels(&self) -> Vec<Vec<[u8; 32]>> {
+        let mut levels = vec![self.leaves.clone()];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hashv(&[&pair[0], &pair[1]]).0)
+                .collect();
+            levels.push(level);
+        }
+        levels
+    }
+
+    fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        self.levels()[..MAX_DEPTH]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| level[(index >> height) ^ 1])
+            .collect()
+    }
+
+    /// The account data of the tree, with the top `canopy_depth` levels
+    /// cached like account compression does: empty subtrees are left zeroed.
+    fn account_data(&self, authority: Pubkey, canopy_depth: usize) -> Vec<u8> {
+        let mut data = vec![1, 0];
+        data.extend((MAX_BUFFER_SIZE as u32).to_le_bytes());
+        data.extend((MAX_DEPTH as u32).to_le_bytes());
+        data.extend(authority.as_ref());
+        data.extend(42u64.to_le_bytes());
+        data.extend([0; 6]);
+        data.extend(bytemuck::bytes_of(&self.cmt));
+
+        let levels = self.levels();
+        for node_index in 2usize..1 << (canopy_depth + 1) {
+            let level = node_index.ilog2() as usize;
+            let height = MAX_DEPTH - level;
+            let node = levels[height][node_index - (1 << level)];
+            if node == empty_node(height) {
+                data.extend([0; 32]);
+            } else {
+                data.extend(node);
+            }
+        }
+        data
+    }
+}
+
+fn leaf(i: usize) -> [u8; 32] {
+    hashv(&[b"leaf", &(i as u64).to_le_bytes()]).0
+}
+
+#[test]
+fn reads_the_tree_header() {
+    let tree = Tree::with_leaves(6);
+    let authority = Pubkey::new_unique();
+    let data = tree.account_data(authority, 0);
+    let reader = ConcurrentMerkleTreeReader::new(&data).unwrap();
+
+    assert_eq!(reader.max_depth(), MAX_DEPTH);
+    assert_eq!(reader.max_buffer_size(), MAX_BUFFER_SIZE);
+    assert_eq!(reader.authority(), authority);
+    assert_eq!(reader.creation_slot(), 42);
+    assert_eq!(reader.sequence_number(), 6);
+    assert_eq!(reader.root(), tree.root());
+    assert_eq!(reader.rightmost_index(), 6);
+    assert!(reader.canopy().is_empty());
+}
+
+#[test]
+fn proves_leaves_without_a_canopy() {
+    let tree = Tree::with_leaves(6);
+    let data = tree.account_data(Pubkey::new_unique(), 0);
+    let reader = ConcurrentMerkleTreeReader::new(&data).unwrap();
+
+    for index in 0..6 {
+        let proof = tree.proof(index);
+        assert!(
+            reader
+                .prove_leaf(tree.root(), leaf(index), &proof, index as u32)
+                .is_ok(),
+            "leaf {index}"
+        );
+    }
+}
+
+#[test]
+fn proves_leaves_with_a_canopy() {
+    let tree = Tree::with_leaves(6);
+    for canopy_depth in 1..=3 {
+        let data = tree.account_data(Pubkey::new_unique(), canopy_depth);
+        let reader = ConcurrentMerkleTreeReader::new(&data).unwrap();
+        assert_eq!(reader.canopy().len(), 32 * ((2 << canopy_depth) - 2));
+
+        for index in 0..6 {
+            let proof = tree.proof(index);
+            let truncated = &proof[..MAX_DEPTH - canopy_depth];
+            assert!(
+                reader
+                    .prove_leaf(tree.root(), leaf(index), truncated, index as u32)
+                    .is_ok(),
+                "leaf {index} with a canopy of depth {canopy_depth}"
+            );
+            assert!(reader
+                .prove_leaf(tree.root(), leaf(index), &proof, index as u32)
+                .is_ok());
+        }
+    }
+}
+
+#[test]
+fn proves_leaves_against_a_stale_root() {
+    let mut tree = Tree::with_leaves(4);
+    let stale_root = tree.root();
+    let stale_proof = tree.proof(1);
+
+    tree.append(leaf(4));
+    tree.set_leaf(3, leaf(100));
+    tree.append(leaf(5));
+    assert_ne!(tree.root(), stale_root);
+
+    for canopy_depth in [0, 2] {
+        let data = tree.account_data(Pubkey::new_unique(), canopy_depth);
+        let reader = ConcurrentMerkleTreeReader::new(&data).unwrap();
+        let proof = &stale_proof[..MAX_DEPTH - canopy_depth];
+        assert!(reader.prove_leaf(stale_root, leaf(1), proof, 1).is_ok());
+        assert!(reader.prove_leaf(stale_root, leaf(3), proof, 3).is_err());
+    }
+
+    // Once the leaf itself changes, its stale proof no longer holds.
+    tree.set_leaf(1, leaf(101));
+    let data = tree.account_data(Pubkey::new_unique(), 0);
+    let reader = ConcurrentMerkleTreeReader::new(&data).unwrap();
+    assert!(reader
+        .prove_leaf(stale_root, leaf(1), &stale_proof, 1)
+        .is_err());
+    assert!(reader
+        .prove_leaf(tree.root(), leaf(101), &tree.proof(1), 1)
+        .is_ok());
+}
+
+#[test]
+fn rejects_tampered_leaves_indices_and_proofs() {
+    let tree = Tree::with_leaves(6);
+    for canopy_depth in [0, 2] {
+        let data = tree.account_data(Pubkey::new_unique(), canopy_depth);
+        let reader = ConcurrentMerkleTreeReader::new(&data).unwrap();
+        let proof = &tree.proof(2)[..MAX_DEPTH - canopy_depth];
+        assert!(reader.prove_leaf(tree.root(), leaf(2), proof, 2).is_ok());
+
+        assert!(reader.prove_leaf(tree.root(), leaf(3), proof, 2).is_err());
+        assert!(reader.prove_leaf(tree.root(), leaf(2), proof, 3).is_err());
+        assert!(reader.prove_leaf(tree.root(), leaf(2), proof, 6).is_err());
+        assert!(reader
+            .prove_leaf(tree.root(), leaf(2), proof, 1 << MAX_DEPTH)
+            .is_err());
+        for depth in 0..proof.len() {
+            let mut tampered = proof.to_vec();
+            tampered[depth][0] ^= 1;
+            assert!(reader
+                .prove_leaf(tree.root(), leaf(2), &tampered, 2)
+                .is_err());
+        }
+    }
+}
+
+#[test]
+fn rejects_malformed_trees() {
+    let tree = Tree::with_leaves(2);
+    let mut data = tree.account_data(Pubkey::new_unique(), 1);
+    assert!(ConcurrentMerkleTreeReader::new(&data[..data.len() / 2]).is_err());
+
+    // A canopy of three nodes is not a full set of levels.
+    data.extend([1; 32]);
+    let reader = ConcurrentMerkleTreeReader::new(&data).unwrap();
+    assert!(reader
+        .prove_leaf(tree.root(), leaf(0), &tree.proof(0), 0)
+        .is_err());
+
+    data[0] = 2;
+    assert!(ConcurrentMerkleTreeReader::new(&data).is_err());
+}
+
+fn metadata_args() -> MetadataArgs {
+    MetadataArgs {
+        name: "Compressed NFT".to_string(),
+        symbol: "CNFT".to_string(),
+        uri: "https://example.com/cnft.json".to_string(),
+        seller_fee_basis_points: 500,
+        primary_sale_happened: true,
+        is_mutable: false,
+        edition_nonce: Some(254),
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: Some(Collection {
+            verified: true,
+            key: Pubkey::new_unique(),
+        }),
+        uses: Some(Uses {
+            use_method: UseMethod::Multiple,
+            remaining: 3,
+            total: 5,
+        }),
+        token_program_version: TokenProgramVersion::Original,
+        creators: vec![
+            Creator {
+                address: Pubkey::new_unique(),
+                verified: true,
+                share: 60,
+            },
+            Creator {
+                address: Pubkey::new_unique(),
+                verified: false,
+                share: 40,
+            },
+        ],
+    }
+}
+
+fn bare_metadata_args() -> MetadataArgs {
+    MetadataArgs {
+        name: String::new(),
+        symbol: String::new(),
+        uri: String::new(),
+        seller_fee_basis_points: 0,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection: None,
+        uses: None,
+        token_program_version: TokenProgramVersion::Token2022,
+        creators: vec![],
+    }
+}
+
+#[test]
+fn hashes_match_bubblegum() {
+    for args in [metadata_args(), bare_metadata_args()] {
+        let data = args.try_to_vec().unwrap();
+        let reader = MetadataArgsReader::new(&data).unwrap();
+
+        assert_eq!(
+            reader.seller_fee_basis_points(),
+            args.seller_fee_basis_points
+        );
+        assert_eq!(
+            reader
+                .collection()
+                .map(|collection| (collection.verified, collection.key)),
+            args.collection
+                .as_ref()
+                .map(|collection| (collection.verified, collection.key))
+        );
+        assert_eq!(
+            hash_metadata(&reader),
+            mpl_bubblegum::hash::hash_metadata(&args).unwrap()
+        );
+        assert_eq!(
+            hash_creators(reader.creators().iter()),
+            mpl_bubblegum::hash::hash_creators(&args.creators)
+        );
+
+        let leaf_schema = LeafSchema {
+            id: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            delegate: Pubkey::new_unique(),
+            nonce: 7,
+            data_hash: hash_metadata(&reader),
+            creator_hash: hash_creators(reader.creators().iter()),
+        };
+        let bubblegum_leaf_schema = mpl_bubblegum::types::LeafSchema::V1 {
+            id: leaf_schema.id,
+            owner: leaf_schema.owner,
+            delegate: leaf_schema.delegate,
+            nonce: leaf_schema.nonce,
+            data_hash: leaf_schema.data_hash,
+            creator_hash: leaf_schema.creator_hash,
+        };
+        assert_eq!(leaf_schema.hash(), bubblegum_leaf_schema.hash());
+    }
+}
+
+#[test]
+fn metadata_args_must_be_complete() {
+    let data = metadata_args().try_to_vec().unwrap();
+    assert!(MetadataArgsReader::new(&data[..data.len() - 1]).is_err());
+
+    let mut trailing = data.clone();
+    trailing.push(0);
+    assert!(MetadataArgsReader::new(&trailing).is_err());
+}
+
+fn merkle_tree_account(key: Pubkey, tree: &Tree, authority: Pubkey) -> TestAccount {
+    AccountBuilder::new()
+        .key(key)
+        .owner(SPL_ACCOUNT_COMPRESSION_ID)
+        .data(tree.account_data(authority, 2))
+        .build()
+}
+
+#[test]
+fn verifies_compressed_nfts() {
+    let data = metadata_args().try_to_vec().unwrap();
+    let nft = CompressedNft {
+        owner: Pubkey::new_unique(),
+        delegate: Pubkey::new_unique(),
+        nonce: 3,
+        index: 3,
+        metadata: MetadataArgsReader::new(&data).unwrap(),
+    };
+    let merkle_tree = Pubkey::new_unique();
+    let mut tree = Tree::with_leaves(3);
+    tree.append(nft.leaf_schema(&merkle_tree).hash());
+    tree.append(leaf(4));
+    let proof = &tree.proof(3)[..MAX_DEPTH - 2];
+    let tree_config = find_tree_config(&merkle_tree).0;
+
+    let mut account = merkle_tree_account(merkle_tree, &tree, tree_config);
+    let leaf_schema = nft.verify(&account.info(), tree.root(), proof).unwrap();
+    assert_eq!(leaf_schema, nft.leaf_schema(&merkle_tree));
+
+    let transferred = CompressedNft {
+        owner: Pubkey::new_unique(),
+        ..nft
+    };
+    assert!(transferred
+        .verify(&account.info(), tree.root(), proof)
+        .is_err());
+    let moved = CompressedNft { index: 2, ..nft };
+    assert!(moved.verify(&account.info(), tree.root(), proof).is_err());
+
+    let mut foreign_tree = merkle_tree_account(merkle_tree, &tree, Pubkey::new_unique());
+    assert!(nft
+        .verify(&foreign_tree.info(), tree.root(), proof)
+        .is_err());
+
+    let mut other_tree = merkle_tree_account(Pubkey::new_unique(), &tree, tree_config);
+    assert!(nft.verify(&other_tree.info(), tree.root(), proof).is_err());
+
+    let mut not_compression_owned = AccountBuilder::new()
+        .key(merkle_tree)
+        .owner(Pubkey::new_unique())
+        .data(tree.account_data(tree_config, 2))
+        .build();
+    assert!(nft
+        .verify(&not_compression_owned.info(), tree.root(), proof)
+        .is_err());
+}
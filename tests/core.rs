@@ -0,0 +1,82 @@
+use hpl_utils::{
+    core::{read_asset, validate_collection, BaseAssetV1, UpdateAuthority, CORE_PROGRAM_ID},
+    fixtures::{AccountBuilder, TestAccount},
+    mpl_core::types::Key,
+    prelude::AnchorSerialize,
+    solana_program::pubkey::Pubkey,
+    ValidateCollectionCreatorOutput,
+};
+
+fn asset(update_authority: UpdateAuthority) -> AccountBuilder {
+    let asset = BaseAssetV1 {
+        key: Key::AssetV1,
+        owner: Pubkey::new_unique(),
+        update_authority,
+        name: "Core Asset".to_string(),
+        uri: "https://example.com/core.json".to_string(),
+        seq: None,
+    };
+    AccountBuilder::new()
+        .owner(CORE_PROGRAM_ID)
+        .data(asset.try_to_vec().unwrap())
+        .rent_exempt()
+}
+
+fn validate(asset: &mut TestAccount, collections: &[Pubkey]) -> bool {
+    validate_collection(&asset.info(), collections).is_ok()
+}
+
+#[test]
+fn validate_collection_accepts_listed_collections() {
+    let collection = Pubkey::new_unique();
+    let mut asset = asset(UpdateAuthority::Collection(collection)).build();
+
+    assert!(matches!(
+        validate_collection(&asset.info(), &[Pubkey::new_unique(), collection]),
+        Ok(ValidateCollectionCreatorOutput::Collection { address }) if address == collection
+    ));
+}
+
+#[test]
+fn validate_collection_rejects_other_collections() {
+    let mut asset = asset(UpdateAuthority::Collection(Pubkey::new_unique())).build();
+
+    assert!(!validate(&mut asset, &[Pubkey::new_unique()]));
+    assert!(!validate(&mut asset, &[]));
+}
+
+#[test]
+fn validate_collection_rejects_assets_without_a_collection() {
+    let authority = Pubkey::new_unique();
+    let mut by_address = asset(UpdateAuthority::Address(authority)).build();
+    let mut without_authority = asset(UpdateAuthority::None).build();
+
+    // The update authority of an asset outside a collection is no collection.
+    assert!(!validate(&mut by_address, &[authority]));
+    assert!(!validate(&mut without_authority, &[Pubkey::default()]));
+}
+
+#[test]
+fn read_asset_checks_the_owner_and_key() {
+    let collection = Pubkey::new_unique();
+    let mut asset_account = asset(UpdateAuthority::Collection(collection)).build();
+    assert_eq!(
+        read_asset(&asset_account.info()).unwrap().update_authority,
+        UpdateAuthority::Collection(collection)
+    );
+
+    let mut foreign = AccountBuilder::new()
+        .owner(Pubkey::new_unique())
+        .data(asset_account.data().to_vec())
+        .build();
+    assert!(read_asset(&foreign.info()).is_err());
+    assert!(!validate(&mut foreign, &[collection]));
+
+    let mut data = asset_account.data().to_vec();
+    data[0] = Key::CollectionV1 as u8;
+    let mut collection_account = AccountBuilder::new()
+        .owner(CORE_PROGRAM_ID)
+        .data(data)
+        .build();
+    assert!(read_asset(&collection_account.info()).is_err());
+}
@@ -0,0 +1,1395 @@
+use hpl_utils::{
+    authorization::{amount_entry, authorization_data, pubkey_entry, PayloadKey},
+    bubblegum::{
+        self, DecompressAccounts, MetadataArgs, MintToCollectionAccounts, VerifyCollectionAccounts,
+        BUBBLEGUM_PROGRAM_ID,
+    },
+    core::{
+        self as core_cpi, AddPluginV1InstructionArgs, AssetAccounts, FreezeDelegate, Plugin,
+        PluginAuthority, UpdatePluginV1InstructionArgs, CORE_PROGRAM_ID,
+    },
+    fixtures::{self, AccountBuilder, TestAccount},
+    mpl_bubblegum::types::TokenProgramVersion,
+    mpl_core,
+    mpl_token_metadata::{
+        self,
+        instruction::{
+            BurnArgs, DelegateArgs, LockArgs, MetadataInstruction, MintArgs, RevokeArgs,
+            TransferArgs, UnlockArgs,
+        },
+    },
+    pda::find_collection_authority_record,
+    prelude::Result,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    test_support::{assert_error, program_error, record_cpis, RecordedCpi},
+    BurnAccounts, DelegateAccounts, LockAccounts, MintAccounts, RevokeAccounts, TransferAccounts,
+    UnlockAccounts, UtilErrors,
+};
+
+/// Checks what the runtime would: every instruction account is passed in,
+/// and no account gains privileges it was not given, unless a PDA signs.
+fn assert_privileges(cpi: &RecordedCpi) {
+    for meta in &cpi.instruction.accounts {
+        if meta.pubkey == cpi.program_id() {
+            continue;
+        }
+        let info = cpi
+            .account_infos
+            .iter()
+            .find(|info| info.pubkey == meta.pubkey)
+            .unwrap_or_else(|| panic!("{} not passed in", meta.pubkey));
+        assert!(!meta.is_writable || info.is_writable, "{}", meta.pubkey);
+        assert!(
+            !meta.is_signer || info.is_signer || !cpi.signer_seeds.is_empty(),
+            "{}",
+            meta.pubkey
+        );
+    }
+}
+
+/// The accounts Token Metadata instructions take for a pNFT held in
+/// `token`, under unique keys, with the flags the instructions need.
+struct Pnft {
+    mint: TestAccount,
+    metadata: TestAccount,
+    edition: TestAccount,
+    token: TestAccount,
+    token_owner: TestAccount,
+    token_record: TestAccount,
+    destination: TestAccount,
+    destination_owner: TestAccount,
+    destination_record: TestAccount,
+    delegate: TestAccount,
+    authority: TestAccount,
+    payer: TestAccount,
+    system_program: TestAccount,
+    sysvar_instructions: TestAccount,
+    token_program: TestAccount,
+    ata_program: TestAccount,
+}
+
+impl Pnft {
+    fn new() -> Self {
+        Self {
+            mint: AccountBuilder::new().writable().build(),
+            metadata: AccountBuilder::new().writable().build(),
+            edition: AccountBuilder::new().writable().build(),
+            token: AccountBuilder::new().writable().build(),
+            token_owner: AccountBuilder::new().signer().build(),
+            token_record: AccountBuilder::new().writable().build(),
+            destination: AccountBuilder::new().writable().build(),
+            destination_owner: AccountBuilder::new().build(),
+            destination_record: AccountBuilder::new().writable().build(),
+            delegate: AccountBuilder::new().build(),
+            authority: AccountBuilder::new().signer().build(),
+            payer: AccountBuilder::new().signer().writable().build(),
+            system_program: fixtures::program(system_program::ID).build(),
+            sysvar_instructions: fixtures::program(sysvar::instructions::ID).build(),
+            token_program: fixtures::program(spl_token::ID).build(),
+            ata_program: fixtures::program(Pubkey::new_unique()).build(),
+        }
+    }
+
+    fn mint(&mut self) -> MintAccounts<'_> {
+        MintAccounts {
+            token: self.token.info(),
+            token_owner: Some(self.token_owner.info()),
+            metadata: self.metadata.info(),
+            master_edition: Some(self.edition.info()),
+            token_record: Some(self.token_record.info()),
+            mint: self.mint.info(),
+            authority: self.authority.info(),
+            delegate_record: None,
+            payer: self.payer.info(),
+            system_program: self.system_program.info(),
+            sysvar_instructions: self.sysvar_instructions.info(),
+            spl_token_program: self.token_program.info(),
+            spl_ata_program: self.ata_program.info(),
+            authorization_rules_program: None,
+            authorization_rules: None,
+        }
+    }
+
+    fn transfer(&mut self) -> TransferAccounts<'_> {
+        TransferAccounts {
+            source_token_account: self.token.info(),
+            source_token_account_owner: self.token_owner.info(),
+            destination_token_account: self.destination.info(),
+            destination_token_account_owner: self.destination_owner.info(),
+            token_mint: self.mint.info(),
+            token_metadata: self.metadata.info(),
+            token_edition: Some(self.edition.info()),
+            source_token_account_record: Some(self.token_record.info()),
+            destination_token_account_record: Some(self.destination_record.info()),
+            authority: self.authority.info(),
+            payer: self.payer.info(),
+            system_program: self.system_program.info(),
+            token_program: self.token_program.info(),
+            associated_token_program: self.ata_program.info(),
+            sysvar_instructions: self.sysvar_instructions.info(),
+            authorization_rules_program: None,
+            authorization_rules: None,
+        }
+    }
+
+    fn lock(&mut self) -> LockAccounts<'_> {
+        LockAccounts {
+            authority: self.authority.info(),
+            token_mint: self.mint.info(),
+            token_account: self.token.info(),
+            token_account_owner: Some(self.token_owner.info()),
+            token_metadata: self.metadata.info(),
+            token_edition: Some(self.edition.info()),
+            token_record: Some(self.token_record.info()),
+            payer: self.payer.info(),
+            system_program: self.system_program.info(),
+            sysvar_instructions: self.sysvar_instructions.info(),
+            token_program: self.token_program.info(),
+            authorization_rules_program: None,
+            authorization_rules: None,
+        }
+    }
+
+    fn delegate(&mut self) -> DelegateAccounts<'_> {
+        DelegateAccounts {
+            delegate_record: None,
+            delegate: self.delegate.info(),
+            metadata: self.metadata.info(),
+            master_edition: Some(self.edition.info()),
+            token_record: Some(self.token_record.info()),
+            mint: self.mint.info(),
+            token_account: self.token.info(),
+            authority: self.authority.info(),
+            payer: self.payer.info(),
+            system_program: self.system_program.info(),
+            sysvar_instructions: self.sysvar_instructions.info(),
+            token_program: self.token_program.info(),
+            authorization_rules_program: None,
+            authorization_rules: None,
+        }
+    }
+}
+
+#[test]
+fn mint_passes_accounts_in_token_metadata_order() {
+    let mut pnft = Pnft::new();
+    let accounts = pnft.mint();
+    let (result, cpis) = record_cpis(|| {
+        accounts.invoke(
+            MintArgs::V1 {
+                amount: 1,
+                authorization_data: None,
+            },
+            None,
+        )
+    });
+
+    result.unwrap();
+    assert_eq!(cpis.len(), 1);
+    let cpi = &cpis[0];
+    assert_eq!(cpi.program_id(), mpl_token_metadata::ID);
+    assert_eq!(
+        cpi.account_keys(),
+        vec![
+            *accounts.token.key,
+            *accounts.token_owner.as_ref().unwrap().key,
+            *accounts.metadata.key,
+            *accounts.master_edition.as_ref().unwrap().key,
+            *accounts.token_record.as_ref().unwrap().key,
+            *accounts.mint.key,
+            *accounts.authority.key,
+            mpl_token_metadata::ID,
+            *accounts.payer.key,
+            system_program::ID,
+            sysvar::instructions::ID,
+            spl_token::ID,
+            *accounts.spl_ata_program.key,
+            mpl_token_metadata::ID,
+            mpl_token_metadata::ID,
+        ]
+    );
+    assert!(cpi.signer_seeds.is_empty());
+    assert_privileges(cpi);
+}
+
+#[test]
+fn burn_forwards_signer_seeds() {
+    // The authority is a PDA, so it only signs through the seeds.
+    let mut authority = AccountBuilder::new().writable().build();
+    let mut metadata = AccountBuilder::new().writable().build();
+    let mut edition = AccountBuilder::new().writable().build();
+    let mut mint = AccountBuilder::new().writable().build();
+    let mut token = AccountBuilder::new().writable().build();
+    let mut system_program = fixtures::program(system_program::ID).build();
+    let mut sysvar_instructions = fixtures::program(sysvar::instructions::ID).build();
+    let mut token_program = fixtures::program(spl_token::ID).build();
+    let accounts = BurnAccounts {
+        authority: authority.info(),
+        collection_metadata: None,
+        metadata: metadata.info(),
+        edition: Some(edition.info()),
+        mint: mint.info(),
+        token: token.info(),
+        master_edition: None,
+        master_edition_mint: None,
+        master_edition_token: None,
+        edition_marker: None,
+        token_record: None,
+        system_program: system_program.info(),
+        sysvar_instructions: sysvar_instructions.info(),
+        spl_token_program: token_program.info(),
+    };
+    let (result, cpis) = record_cpis(|| {
+        accounts.invoke(
+            BurnArgs::V1 { amount: 1 },
+            Some(&[&[b"authority".as_ref(), &[254]]]),
+        )
+    });
+
+    result.unwrap();
+    let cpi = &cpis[0];
+    assert_eq!(
+        cpi.signer_seeds,
+        vec![vec![b"authority".to_vec(), vec![254]]]
+    );
+    assert_eq!(cpi.instruction.accounts[0].pubkey, *accounts.authority.key);
+    assert!(cpi.instruction.accounts[0].is_signer);
+    assert_privileges(cpi);
+}
+
+#[test]
+fn transfer_marks_owner_and_destination() {
+    let mut pnft = Pnft::new();
+    let accounts = pnft.transfer();
+    let (result, cpis) = record_cpis(|| {
+        hpl_utils::transfer(
+            1,
+            accounts.source_token_account.clone(),
+            accounts.source_token_account_owner.clone(),
+            accounts.destination_token_account.clone(),
+            accounts.destination_token_account_owner.clone(),
+            accounts.token_mint.clone(),
+            accounts.token_metadata.clone(),
+            accounts.token_edition.clone(),
+            accounts.source_token_account_record.clone(),
+            accounts.destination_token_account_record.clone(),
+            accounts.authority.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.token_program.clone(),
+            accounts.associated_token_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            None,
+            None,
+            None,
+            None,
+        )
+    });
+
+    result.unwrap();
+    let metas = &cpis[0].instruction.accounts;
+    assert_eq!(metas[2].pubkey, *accounts.destination_token_account.key);
+    assert!(metas[2].is_writable);
+    assert_eq!(
+        metas[7].pubkey,
+        *accounts.source_token_account_record.as_ref().unwrap().key
+    );
+    assert_eq!(
+        metas[8].pubkey,
+        *accounts
+            .destination_token_account_record
+            .as_ref()
+            .unwrap()
+            .key
+    );
+    assert!(metas[9].is_signer);
+    assert_privileges(&cpis[0]);
+}
+
+#[test]
+fn lock_and_unlock_pass_the_same_accounts() {
+    let mut pnft = Pnft::new();
+    let accounts = pnft.lock();
+    let unlock_accounts = UnlockAccounts {
+        authority: accounts.authority.clone(),
+        token_mint: accounts.token_mint.clone(),
+        token_account: accounts.token_account.clone(),
+        token_account_owner: accounts.token_account_owner.clone(),
+        token_metadata: accounts.token_metadata.clone(),
+        token_edition: accounts.token_edition.clone(),
+        token_record: accounts.token_record.clone(),
+        payer: accounts.payer.clone(),
+        system_program: accounts.system_program.clone(),
+        sysvar_instructions: accounts.sysvar_instructions.clone(),
+        token_program: accounts.token_program.clone(),
+        authorization_rules_program: None,
+        authorization_rules: None,
+    };
+    let (result, cpis) = record_cpis(|| {
+        accounts.invoke(
+            LockArgs::V1 {
+                authorization_data: None,
+            },
+            None,
+        )?;
+        unlock_accounts.invoke(
+            UnlockArgs::V1 {
+                authorization_data: None,
+            },
+            None,
+        )
+    });
+
+    result.unwrap();
+    assert_eq!(cpis.len(), 2);
+    assert_eq!(cpis[0].instruction.accounts, cpis[1].instruction.accounts);
+    assert_ne!(cpis[0].instruction.data, cpis[1].instruction.data);
+    cpis.iter().for_each(assert_privileges);
+}
+
+fn metadata_instruction(cpi: &RecordedCpi) -> MetadataInstruction {
+    // Token Metadata 1.13.2 is still on borsh 0.9.
+    #[allow(deprecated)]
+    hpl_utils::solana_program::borsh0_9::try_from_slice_unchecked(&cpi.instruction.data).unwrap()
+}
+
+#[test]
+fn transfer_serializes_the_authorization_data() {
+    let mut pnft = Pnft::new();
+    let accounts = pnft.transfer();
+    let data = authorization_data([
+        pubkey_entry(
+            PayloadKey::Destination,
+            *accounts.destination_token_account_owner.key,
+        ),
+        amount_entry(1),
+    ]);
+    let (result, cpis) = record_cpis(|| {
+        hpl_utils::transfer(
+            1,
+            accounts.source_token_account.clone(),
+            accounts.source_token_account_owner.clone(),
+            accounts.destination_token_account.clone(),
+            accounts.destination_token_account_owner.clone(),
+            accounts.token_mint.clone(),
+            accounts.token_metadata.clone(),
+            accounts.token_edition.clone(),
+            accounts.source_token_account_record.clone(),
+            accounts.destination_token_account_record.clone(),
+            accounts.authority.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.token_program.clone(),
+            accounts.associated_token_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            None,
+            None,
+            Some(data.clone()),
+            None,
+        )
+    });
+
+    result.unwrap();
+    let MetadataInstruction::Transfer(TransferArgs::V1 {
+        amount,
+        authorization_data,
+    }) = metadata_instruction(&cpis[0])
+    else {
+        panic!("not a transfer");
+    };
+    assert_eq!(amount, 1);
+    assert_eq!(authorization_data, Some(data));
+}
+
+#[test]
+fn lock_and_unlock_serialize_the_authorization_data() {
+    let mut pnft = Pnft::new();
+    let accounts = pnft.lock();
+    let data = authorization_data([pubkey_entry(PayloadKey::Authority, *accounts.authority.key)]);
+    let (result, cpis) = record_cpis(|| {
+        hpl_utils::lock(
+            accounts.authority.clone(),
+            accounts.token_mint.clone(),
+            accounts.token_account.clone(),
+            accounts.token_account_owner.clone(),
+            accounts.token_metadata.clone(),
+            accounts.token_edition.clone(),
+            accounts.token_record.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            None,
+            None,
+            Some(data.clone()),
+            None,
+        )?;
+        hpl_utils::unlock(
+            accounts.authority.clone(),
+            accounts.token_mint.clone(),
+            accounts.token_account.clone(),
+            accounts.token_account_owner.clone(),
+            accounts.token_metadata.clone(),
+            accounts.token_edition.clone(),
+            accounts.token_record.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            None,
+            None,
+            Some(data.clone()),
+            None,
+        )
+    });
+
+    result.unwrap();
+    let MetadataInstruction::Lock(LockArgs::V1 { authorization_data }) =
+        metadata_instruction(&cpis[0])
+    else {
+        panic!("not a lock");
+    };
+    assert_eq!(authorization_data, Some(data.clone()));
+    let MetadataInstruction::Unlock(UnlockArgs::V1 { authorization_data }) =
+        metadata_instruction(&cpis[1])
+    else {
+        panic!("not an unlock");
+    };
+    assert_eq!(authorization_data, Some(data));
+}
+
+#[test]
+fn delegate_and_revoke_pass_the_same_accounts() {
+    let mut pnft = Pnft::new();
+    let accounts = pnft.delegate();
+    let revoke_accounts = RevokeAccounts {
+        delegate_record: accounts.delegate_record.clone(),
+        delegate: accounts.delegate.clone(),
+        metadata: accounts.metadata.clone(),
+        master_edition: accounts.master_edition.clone(),
+        token_record: accounts.token_record.clone(),
+        mint: accounts.mint.clone(),
+        token_account: accounts.token_account.clone(),
+        authority: accounts.authority.clone(),
+        payer: accounts.payer.clone(),
+        system_program: accounts.system_program.clone(),
+        sysvar_instructions: accounts.sysvar_instructions.clone(),
+        token_program: accounts.token_program.clone(),
+        authorization_rules_program: None,
+        authorization_rules: None,
+    };
+    let (result, cpis) = record_cpis(|| {
+        accounts.invoke(
+            DelegateArgs::UtilityV1 {
+                amount: 1,
+                authorization_data: None,
+            },
+            None,
+        )?;
+        revoke_accounts.invoke(RevokeArgs::UtilityV1, None)
+    });
+
+    result.unwrap();
+    assert_eq!(cpis[0].instruction.accounts, cpis[1].instruction.accounts);
+    assert_eq!(
+        cpis[0].instruction.accounts[0].pubkey,
+        mpl_token_metadata::ID
+    );
+    cpis.iter().for_each(assert_privileges);
+}
+
+#[test]
+fn missing_account_infos_are_rejected() {
+    let mut payer = AccountBuilder::new().signer().writable().build();
+    let payer = payer.info();
+    let recipient = Pubkey::new_unique();
+    let instruction = Instruction::new_with_bytes(
+        system_program::ID,
+        &[],
+        vec![
+            AccountMeta::new(*payer.key, true),
+            AccountMeta::new(recipient, false),
+        ],
+    );
+
+    let (result, cpis) = record_cpis(|| invoke(&instruction, std::slice::from_ref(&payer)));
+
+    assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    assert_eq!(cpis.len(), 1);
+    assert_eq!(cpis[0].account_keys(), vec![*payer.key, recipient]);
+}
+
+/// A Core asset in a collection, with the accounts Core instructions on it
+/// take.
+struct CoreAsset {
+    asset: TestAccount,
+    collection: TestAccount,
+    payer: TestAccount,
+    authority: TestAccount,
+    new_owner: TestAccount,
+    system_program: TestAccount,
+    log_wrapper: TestAccount,
+    core_program: TestAccount,
+}
+
+impl CoreAsset {
+    fn new() -> Self {
+        Self {
+            asset: AccountBuilder::new().writable().build(),
+            collection: AccountBuilder::new().writable().build(),
+            payer: AccountBuilder::new().signer().writable().build(),
+            authority: AccountBuilder::new().signer().build(),
+            new_owner: AccountBuilder::new().build(),
+            system_program: fixtures::program(system_program::ID).build(),
+            log_wrapper: fixtures::program(Pubkey::new_unique()).build(),
+            core_program: fixtures::program(CORE_PROGRAM_ID).build(),
+        }
+    }
+
+    fn accounts(&mut self) -> AssetAccounts<'_> {
+        AssetAccounts {
+            asset: self.asset.info(),
+            collection: Some(self.collection.info()),
+            payer: self.payer.info(),
+            authority: Some(self.authority.info()),
+            system_program: self.system_program.info(),
+            log_wrapper: Some(self.log_wrapper.info()),
+            core_program: self.core_program.info(),
+        }
+    }
+
+    /// Accounts of a transfer leaving the optional programs out.
+    fn transfer(&mut self) -> core_cpi::TransferAccounts<'_> {
+        core_cpi::TransferAccounts {
+            asset: self.asset.info(),
+            collection: Some(self.collection.info()),
+            payer: self.payer.info(),
+            authority: Some(self.authority.info()),
+            new_owner: self.new_owner.info(),
+            system_program: None,
+            log_wrapper: None,
+            core_program: self.core_program.info(),
+        }
+    }
+}
+
+#[test]
+fn core_create_stands_the_program_id_in_for_omitted_accounts() {
+    let mut asset = AccountBuilder::new().signer().writable().build();
+    let mut payer = AccountBuilder::new().signer().writable().build();
+    let mut system_program = fixtures::program(system_program::ID).build();
+    let mut core_program = fixtures::program(CORE_PROGRAM_ID).build();
+    let accounts = core_cpi::CreateAccounts {
+        asset: asset.info(),
+        collection: None,
+        authority: None,
+        payer: payer.info(),
+        owner: None,
+        update_authority: None,
+        system_program: system_program.info(),
+        log_wrapper: None,
+        core_program: core_program.info(),
+    };
+    let (result, cpis) = record_cpis(|| {
+        core_cpi::create(
+            "Asset".to_string(),
+            "https://example.com/asset.json".to_string(),
+            None,
+            accounts.clone(),
+            None,
+        )
+    });
+
+    result.unwrap();
+    let cpi = &cpis[0];
+    assert_eq!(cpi.program_id(), CORE_PROGRAM_ID);
+    assert_eq!(
+        cpi.instruction.accounts,
+        vec![
+            AccountMeta::new(*accounts.asset.key, true),
+            AccountMeta::new_readonly(CORE_PROGRAM_ID, false),
+            AccountMeta::new_readonly(CORE_PROGRAM_ID, false),
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new_readonly(CORE_PROGRAM_ID, false),
+            AccountMeta::new_readonly(CORE_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(CORE_PROGRAM_ID, false),
+        ]
+    );
+    assert_eq!(
+        cpi.account_infos
+            .iter()
+            .map(|info| info.pubkey)
+            .collect::<Vec<_>>(),
+        vec![
+            *accounts.asset.key,
+            *accounts.payer.key,
+            system_program::ID,
+            CORE_PROGRAM_ID
+        ]
+    );
+    assert_privileges(cpi);
+}
+
+#[test]
+fn core_transfer_marks_the_authority_and_new_owner() {
+    let mut asset = CoreAsset::new();
+    let accounts = asset.transfer();
+    let (result, cpis) = record_cpis(|| {
+        core_cpi::transfer(accounts.clone(), Some(&[&[b"authority".as_ref(), &[255]]]))
+    });
+
+    result.unwrap();
+    let cpi = &cpis[0];
+    assert_eq!(
+        cpi.instruction.accounts,
+        vec![
+            AccountMeta::new(*accounts.asset.key, false),
+            AccountMeta::new_readonly(*accounts.collection.as_ref().unwrap().key, false),
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new_readonly(*accounts.authority.as_ref().unwrap().key, true),
+            AccountMeta::new_readonly(*accounts.new_owner.key, false),
+            AccountMeta::new_readonly(CORE_PROGRAM_ID, false),
+            AccountMeta::new_readonly(CORE_PROGRAM_ID, false),
+        ]
+    );
+    assert_eq!(
+        cpi.signer_seeds,
+        vec![vec![b"authority".to_vec(), vec![255]]]
+    );
+    assert_privileges(cpi);
+}
+
+#[test]
+fn core_asset_instructions_pass_accounts_in_core_order() {
+    let mut asset = CoreAsset::new();
+    let accounts = asset.accounts();
+    let (result, cpis) = record_cpis(|| {
+        core_cpi::burn(accounts.clone(), None)?;
+        core_cpi::update(None, None, None, accounts.clone(), None)?;
+        core_cpi::add_plugin(
+            Plugin::FreezeDelegate(FreezeDelegate { frozen: false }),
+            None,
+            accounts.clone(),
+            None,
+        )?;
+        core_cpi::update_plugin(
+            Plugin::FreezeDelegate(FreezeDelegate { frozen: true }),
+            accounts.clone(),
+            None,
+        )
+    });
+
+    result.unwrap();
+    assert_eq!(cpis.len(), 4);
+    let expected = vec![
+        *accounts.asset.key,
+        *accounts.collection.as_ref().unwrap().key,
+        *accounts.payer.key,
+        *accounts.authority.as_ref().unwrap().key,
+        system_program::ID,
+        *accounts.log_wrapper.as_ref().unwrap().key,
+    ];
+    for cpi in &cpis {
+        assert_eq!(cpi.program_id(), CORE_PROGRAM_ID);
+        assert_eq!(cpi.account_keys(), expected);
+        let metas = &cpi.instruction.accounts;
+        assert!(metas[0].is_writable && !metas[0].is_signer);
+        assert!(metas[2].is_signer && metas[2].is_writable);
+        assert!(metas[3].is_signer && !metas[3].is_writable);
+        assert_privileges(cpi);
+    }
+}
+
+#[test]
+fn core_freeze_delegate_helpers_set_the_plugin() {
+    let mut asset = CoreAsset::new();
+    let accounts = asset.accounts();
+    let delegate = Pubkey::new_unique();
+    let (result, cpis) = record_cpis(|| {
+        core_cpi::approve_freeze_delegate(delegate, accounts.clone(), None)?;
+        core_cpi::freeze(accounts.clone(), None)?;
+        core_cpi::thaw(accounts.clone(), None)
+    });
+
+    result.unwrap();
+    let add_plugin = mpl_core::instructions::AddPluginV1 {
+        asset: *accounts.asset.key,
+        collection: None,
+        payer: *accounts.payer.key,
+        authority: None,
+        system_program: system_program::ID,
+        log_wrapper: None,
+    };
+    let update_plugin = |frozen| {
+        mpl_core::instructions::UpdatePluginV1 {
+            asset: *accounts.asset.key,
+            collection: None,
+            payer: *accounts.payer.key,
+            authority: None,
+            system_program: system_program::ID,
+            log_wrapper: None,
+        }
+        .instruction(UpdatePluginV1InstructionArgs {
+            plugin: Plugin::FreezeDelegate(FreezeDelegate { frozen }),
+        })
+    };
+    assert_eq!(
+        cpis.iter()
+            .map(|cpi| cpi.instruction.data.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            add_plugin
+                .instruction(AddPluginV1InstructionArgs {
+                    plugin: Plugin::FreezeDelegate(FreezeDelegate { frozen: false }),
+                    init_authority: Some(PluginAuthority::Address { address: delegate }),
+                })
+                .data,
+            update_plugin(true).data,
+            update_plugin(false).data,
+        ]
+    );
+    cpis.iter().for_each(assert_privileges);
+}
+
+/// A collection with `delegate` as a collection authority to approve or
+/// revoke, and its authority record.
+struct CollectionAuthority {
+    mint: TestAccount,
+    metadata: TestAccount,
+    update_authority: TestAccount,
+    delegate: TestAccount,
+    record: TestAccount,
+    system_program: TestAccount,
+}
+
+impl CollectionAuthority {
+    fn new() -> Self {
+        let mint = AccountBuilder::new().build();
+        let delegate = AccountBuilder::new().build();
+        let (record, _) = find_collection_authority_record(&mint.key(), &delegate.key());
+        Self {
+            record: AccountBuilder::new().key(record).writable().build(),
+            metadata: AccountBuilder::new().writable().build(),
+            update_authority: AccountBuilder::new().signer().writable().build(),
+            system_program: fixtures::program(system_program::ID).build(),
+            mint,
+            delegate,
+        }
+    }
+
+    /// Approves `delegate` with `record` as its authority record, or with its
+    /// own without one.
+    fn approve(&mut self, record: Option<&mut TestAccount>) -> Result<()> {
+        let update_authority = self.update_authority.info();
+        hpl_utils::approve(
+            self.mint.info(),
+            self.metadata.info(),
+            update_authority.clone(),
+            self.delegate.info(),
+            record.unwrap_or(&mut self.record).info(),
+            self.system_program.info(),
+            update_authority,
+            None,
+        )
+    }
+
+    /// Revokes `delegate`, as `approve` approves it.
+    fn revoke(&mut self, record: Option<&mut TestAccount>) -> Result<()> {
+        hpl_utils::revoke_approval(
+            self.mint.info(),
+            self.metadata.info(),
+            self.update_authority.info(),
+            self.delegate.info(),
+            record.unwrap_or(&mut self.record).info(),
+            None,
+        )
+    }
+}
+
+#[test]
+fn collection_approve_and_revoke_pass_accounts_in_token_metadata_order() {
+    let mut collection = CollectionAuthority::new();
+    let (result, cpis) = record_cpis(|| {
+        collection.approve(None)?;
+        collection.revoke(None)
+    });
+
+    result.unwrap();
+    assert_eq!(cpis.len(), 2);
+    let (approve, revoke) = (&cpis[0], &cpis[1]);
+    assert_eq!(approve.program_id(), mpl_token_metadata::ID);
+    assert_eq!(
+        approve.account_keys(),
+        vec![
+            collection.record.key(),
+            collection.delegate.key(),
+            collection.update_authority.key(),
+            collection.update_authority.key(),
+            collection.metadata.key(),
+            collection.mint.key(),
+            system_program::ID,
+        ]
+    );
+    assert_eq!(revoke.program_id(), mpl_token_metadata::ID);
+    assert_eq!(
+        revoke.account_keys(),
+        vec![
+            collection.record.key(),
+            collection.delegate.key(),
+            collection.update_authority.key(),
+            collection.metadata.key(),
+            collection.mint.key(),
+        ]
+    );
+
+    assert_eq!(
+        signers(approve),
+        vec![false, false, true, true, false, false, false]
+    );
+    assert_eq!(signers(revoke), vec![false, false, true, false, false]);
+    assert!(approve.instruction.accounts[0].is_writable);
+    assert!(revoke.instruction.accounts[0].is_writable);
+    cpis.iter().for_each(assert_privileges);
+}
+
+#[test]
+fn collection_approve_and_revoke_reject_the_record_of_another_authority() {
+    let mut collection = CollectionAuthority::new();
+    let (other_record, _) =
+        find_collection_authority_record(&collection.mint.key(), &Pubkey::new_unique());
+    let mut other_record = AccountBuilder::new().key(other_record).writable().build();
+    let (_, cpis) = record_cpis(|| {
+        assert_error(
+            collection.approve(Some(&mut other_record)),
+            UtilErrors::InvalidNewAuthorityRecord,
+        );
+        assert_error(
+            collection.revoke(Some(&mut other_record)),
+            UtilErrors::InvalidCollectionAuthorityRecord,
+        );
+    });
+
+    assert!(cpis.is_empty());
+}
+
+#[test]
+fn collection_approve_requires_the_system_program() {
+    let mut collection = CollectionAuthority::new();
+    collection.system_program = fixtures::program(Pubkey::new_unique()).build();
+    let (result, cpis) = record_cpis(|| collection.approve(None));
+
+    assert_eq!(
+        program_error(result.unwrap_err()),
+        ProgramError::IncorrectProgramId
+    );
+    assert!(cpis.is_empty());
+}
+
+/// A compressed NFT proved by two nodes. Without a delegate, the owner is
+/// passed as the leaf delegate, which Bubblegum expects then.
+struct Cnft {
+    tree_config: TestAccount,
+    leaf_owner: TestAccount,
+    delegate: TestAccount,
+    new_leaf_owner: TestAccount,
+    new_delegate: TestAccount,
+    voucher: TestAccount,
+    merkle_tree: TestAccount,
+    log_wrapper: TestAccount,
+    compression_program: TestAccount,
+    system_program: TestAccount,
+    proof: Vec<TestAccount>,
+}
+
+impl Cnft {
+    fn new(owner_signs: bool) -> Self {
+        let leaf_owner = AccountBuilder::new().writable();
+        Self {
+            tree_config: AccountBuilder::new().writable().build(),
+            leaf_owner: if owner_signs {
+                leaf_owner.signer()
+            } else {
+                leaf_owner
+            }
+            .build(),
+            delegate: AccountBuilder::new().signer().build(),
+            new_leaf_owner: AccountBuilder::new().build(),
+            new_delegate: AccountBuilder::new().build(),
+            voucher: AccountBuilder::new().writable().build(),
+            merkle_tree: AccountBuilder::new().writable().build(),
+            log_wrapper: fixtures::program(Pubkey::new_unique()).build(),
+            compression_program: fixtures::program(Pubkey::new_unique()).build(),
+            system_program: fixtures::program(system_program::ID).build(),
+            proof: vec![AccountBuilder::new().build(), AccountBuilder::new().build()],
+        }
+    }
+
+    fn transfer(&mut self, delegated: bool, delegate_as_signer: bool) -> Result<()> {
+        let leaf_owner = self.leaf_owner.info();
+        let leaf_delegate = match delegated {
+            true => self.delegate.info(),
+            false => leaf_owner.clone(),
+        };
+        bubblegum::transfer(
+            [1; 32],
+            [2; 32],
+            [3; 32],
+            0,
+            0,
+            bubblegum::TransferAccounts {
+                tree_config: self.tree_config.info(),
+                leaf_owner,
+                leaf_delegate,
+                new_leaf_owner: self.new_leaf_owner.info(),
+                merkle_tree: self.merkle_tree.info(),
+                log_wrapper: self.log_wrapper.info(),
+                compression_program: self.compression_program.info(),
+                system_program: self.system_program.info(),
+                proof: self.proof.iter_mut().map(TestAccount::info).collect(),
+                delegate_as_signer,
+            },
+            None,
+        )
+    }
+
+    fn burn(&mut self, delegated: bool, delegate_as_signer: bool) -> Result<()> {
+        let leaf_owner = self.leaf_owner.info();
+        let leaf_delegate = match delegated {
+            true => self.delegate.info(),
+            false => leaf_owner.clone(),
+        };
+        bubblegum::burn(
+            [1; 32],
+            [2; 32],
+            [3; 32],
+            0,
+            0,
+            bubblegum::BurnAccounts {
+                tree_config: self.tree_config.info(),
+                leaf_owner,
+                leaf_delegate,
+                merkle_tree: self.merkle_tree.info(),
+                log_wrapper: self.log_wrapper.info(),
+                compression_program: self.compression_program.info(),
+                system_program: self.system_program.info(),
+                proof: self.proof.iter_mut().map(TestAccount::info).collect(),
+                delegate_as_signer,
+            },
+            None,
+        )
+    }
+
+    /// Delegates the leaf, still without a delegate, to `new_delegate`.
+    fn delegate(&mut self) -> Result<()> {
+        let leaf_owner = self.leaf_owner.info();
+        bubblegum::delegate(
+            [1; 32],
+            [2; 32],
+            [3; 32],
+            0,
+            0,
+            bubblegum::DelegateAccounts {
+                tree_config: self.tree_config.info(),
+                previous_leaf_delegate: leaf_owner.clone(),
+                leaf_owner,
+                new_leaf_delegate: self.new_delegate.info(),
+                merkle_tree: self.merkle_tree.info(),
+                log_wrapper: self.log_wrapper.info(),
+                compression_program: self.compression_program.info(),
+                system_program: self.system_program.info(),
+                proof: self.proof.iter_mut().map(TestAccount::info).collect(),
+            },
+            None,
+        )
+    }
+
+    fn redeem(&mut self) -> Result<()> {
+        let leaf_owner = self.leaf_owner.info();
+        bubblegum::redeem(
+            [1; 32],
+            [2; 32],
+            [3; 32],
+            0,
+            0,
+            bubblegum::RedeemAccounts {
+                tree_config: self.tree_config.info(),
+                leaf_delegate: leaf_owner.clone(),
+                leaf_owner,
+                merkle_tree: self.merkle_tree.info(),
+                voucher: self.voucher.info(),
+                log_wrapper: self.log_wrapper.info(),
+                compression_program: self.compression_program.info(),
+                system_program: self.system_program.info(),
+                proof: self.proof.iter_mut().map(TestAccount::info).collect(),
+            },
+            None,
+        )
+    }
+
+    fn proof_metas(&self) -> Vec<AccountMeta> {
+        self.proof
+            .iter()
+            .map(|node| AccountMeta::new_readonly(node.key(), false))
+            .collect()
+    }
+}
+
+fn signers(cpi: &RecordedCpi) -> Vec<bool> {
+    cpi.instruction
+        .accounts
+        .iter()
+        .map(|meta| meta.is_signer)
+        .collect()
+}
+
+#[test]
+fn bubblegum_transfer_and_burn_pass_the_owner_as_the_missing_delegate() {
+    let mut cnft = Cnft::new(true);
+    let (result, cpis) = record_cpis(|| {
+        cnft.transfer(false, false)?;
+        cnft.burn(false, false)
+    });
+
+    result.unwrap();
+    let (transfer, burn) = (&cpis[0], &cpis[1]);
+    assert_eq!(transfer.program_id(), BUBBLEGUM_PROGRAM_ID);
+    assert_eq!(
+        transfer.instruction.accounts[1].pubkey,
+        cnft.leaf_owner.key()
+    );
+    assert_eq!(
+        transfer.instruction.accounts[2].pubkey,
+        cnft.leaf_owner.key()
+    );
+    assert_eq!(
+        signers(transfer),
+        vec![false, true, false, false, false, false, false, false, false, false]
+    );
+    assert_eq!(transfer.instruction.accounts[8..], cnft.proof_metas());
+
+    assert_eq!(burn.program_id(), BUBBLEGUM_PROGRAM_ID);
+    assert_eq!(
+        burn.account_keys()[..7],
+        [
+            cnft.tree_config.key(),
+            cnft.leaf_owner.key(),
+            cnft.leaf_owner.key(),
+            cnft.merkle_tree.key(),
+            cnft.log_wrapper.key(),
+            cnft.compression_program.key(),
+            system_program::ID,
+        ]
+    );
+    assert_eq!(
+        signers(burn),
+        vec![false, true, false, false, false, false, false, false, false]
+    );
+    assert_eq!(burn.instruction.accounts[7..], cnft.proof_metas());
+    cpis.iter().for_each(assert_privileges);
+}
+
+#[test]
+fn bubblegum_leaf_owner_signs_unless_the_delegate_does() {
+    let mut cnft = Cnft::new(false);
+    let (result, cpis) = record_cpis(|| {
+        cnft.transfer(true, true)?;
+        cnft.burn(true, true)
+    });
+
+    result.unwrap();
+    for cpi in &cpis {
+        let metas = &cpi.instruction.accounts;
+        assert_eq!(metas[2].pubkey, cnft.delegate.key());
+        assert!(!metas[1].is_signer);
+        assert!(metas[2].is_signer);
+        assert_privileges(cpi);
+    }
+
+    // Without the delegate signing, the owner has to.
+    let (result, cpis) = record_cpis(|| cnft.transfer(true, false));
+    result.unwrap();
+    assert!(cpis[0].instruction.accounts[1].is_signer);
+    assert!(!cpis[0].instruction.accounts[2].is_signer);
+}
+
+#[test]
+fn bubblegum_delegate_and_redeem_require_the_owner() {
+    let mut cnft = Cnft::new(true);
+    let (result, cpis) = record_cpis(|| {
+        cnft.delegate()?;
+        cnft.redeem()
+    });
+
+    result.unwrap();
+    let (delegate, redeem) = (&cpis[0], &cpis[1]);
+    assert_eq!(
+        delegate.account_keys()[..4],
+        [
+            cnft.tree_config.key(),
+            cnft.leaf_owner.key(),
+            cnft.leaf_owner.key(),
+            cnft.new_delegate.key(),
+        ]
+    );
+    assert_eq!(
+        signers(delegate),
+        vec![false, true, false, false, false, false, false, false, false, false]
+    );
+    assert_eq!(delegate.instruction.accounts[8..], cnft.proof_metas());
+
+    assert_eq!(
+        redeem.instruction.accounts[4],
+        AccountMeta::new(cnft.voucher.key(), false)
+    );
+    assert_eq!(
+        signers(redeem),
+        vec![false, true, false, false, false, false, false, false, false, false]
+    );
+    assert_eq!(redeem.instruction.accounts[8..], cnft.proof_metas());
+    cpis.iter().for_each(assert_privileges);
+}
+
+#[test]
+fn bubblegum_decompress_passes_accounts_in_bubblegum_order() {
+    let mut voucher = AccountBuilder::new().writable().build();
+    let mut leaf_owner = AccountBuilder::new().signer().writable().build();
+    let mut token_account = AccountBuilder::new().writable().build();
+    let mut mint = AccountBuilder::new().writable().build();
+    let mut mint_authority = AccountBuilder::new().writable().build();
+    let mut metadata = AccountBuilder::new().writable().build();
+    let mut master_edition = AccountBuilder::new().writable().build();
+    let mut system_program = fixtures::program(system_program::ID).build();
+    let mut rent = fixtures::rent_sysvar().build();
+    let mut token_metadata_program = fixtures::program(mpl_token_metadata::ID).build();
+    let mut token_program = fixtures::program(spl_token::ID).build();
+    let mut associated_token_program = fixtures::program(Pubkey::new_unique()).build();
+    let mut log_wrapper = fixtures::program(Pubkey::new_unique()).build();
+    let accounts = DecompressAccounts {
+        voucher: voucher.info(),
+        leaf_owner: leaf_owner.info(),
+        token_account: token_account.info(),
+        mint: mint.info(),
+        mint_authority: mint_authority.info(),
+        metadata: metadata.info(),
+        master_edition: master_edition.info(),
+        system_program: system_program.info(),
+        rent: rent.info(),
+        token_metadata_program: token_metadata_program.info(),
+        token_program: token_program.info(),
+        associated_token_program: associated_token_program.info(),
+        log_wrapper: log_wrapper.info(),
+    };
+    let (result, cpis) =
+        record_cpis(|| bubblegum::decompress(bubblegum_metadata_args(), accounts.clone(), None));
+
+    result.unwrap();
+    let cpi = &cpis[0];
+    assert_eq!(
+        cpi.account_keys(),
+        vec![
+            *accounts.voucher.key,
+            *accounts.leaf_owner.key,
+            *accounts.token_account.key,
+            *accounts.mint.key,
+            *accounts.mint_authority.key,
+            *accounts.metadata.key,
+            *accounts.master_edition.key,
+            system_program::ID,
+            sysvar::rent::ID,
+            mpl_token_metadata::ID,
+            spl_token::ID,
+            *accounts.associated_token_program.key,
+            *accounts.log_wrapper.key,
+        ]
+    );
+    assert_eq!(
+        signers(cpi),
+        vec![
+            false, true, false, false, false, false, false, false, false, false, false, false,
+            false
+        ]
+    );
+    assert_privileges(cpi);
+}
+
+fn bubblegum_metadata_args() -> MetadataArgs {
+    MetadataArgs {
+        name: "Compressed NFT".to_string(),
+        symbol: "CNFT".to_string(),
+        uri: "https://example.com/cnft.json".to_string(),
+        seller_fee_basis_points: 500,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection: None,
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: vec![],
+    }
+}
+
+/// A tree whose leaves join a collection, verified by one proof node.
+struct BubblegumCollection {
+    tree_config: TestAccount,
+    leaf_owner: TestAccount,
+    leaf_delegate: TestAccount,
+    merkle_tree: TestAccount,
+    payer: TestAccount,
+    tree_creator_or_delegate: TestAccount,
+    collection_authority: TestAccount,
+    collection_authority_record: Option<TestAccount>,
+    collection_mint: TestAccount,
+    collection_metadata: TestAccount,
+    collection_edition: TestAccount,
+    bubblegum_signer: TestAccount,
+    log_wrapper: TestAccount,
+    compression_program: TestAccount,
+    token_metadata_program: TestAccount,
+    system_program: TestAccount,
+    bubblegum_program: TestAccount,
+    proof: TestAccount,
+}
+
+impl BubblegumCollection {
+    fn new() -> Self {
+        Self {
+            tree_config: AccountBuilder::new().writable().build(),
+            leaf_owner: AccountBuilder::new().build(),
+            leaf_delegate: AccountBuilder::new().build(),
+            merkle_tree: AccountBuilder::new().writable().build(),
+            payer: AccountBuilder::new().signer().writable().build(),
+            tree_creator_or_delegate: AccountBuilder::new().signer().build(),
+            collection_authority: AccountBuilder::new().signer().build(),
+            collection_authority_record: None,
+            collection_mint: AccountBuilder::new().build(),
+            collection_metadata: AccountBuilder::new().writable().build(),
+            collection_edition: AccountBuilder::new().build(),
+            bubblegum_signer: AccountBuilder::new().build(),
+            log_wrapper: fixtures::program(Pubkey::new_unique()).build(),
+            compression_program: fixtures::program(Pubkey::new_unique()).build(),
+            token_metadata_program: fixtures::program(mpl_token_metadata::ID).build(),
+            system_program: fixtures::program(system_program::ID).build(),
+            bubblegum_program: fixtures::program(BUBBLEGUM_PROGRAM_ID).build(),
+            proof: AccountBuilder::new().build(),
+        }
+    }
+
+    fn mint(&mut self) -> MintToCollectionAccounts<'_> {
+        MintToCollectionAccounts {
+            tree_config: self.tree_config.info(),
+            leaf_owner: self.leaf_owner.info(),
+            leaf_delegate: self.leaf_delegate.info(),
+            merkle_tree: self.merkle_tree.info(),
+            payer: self.payer.info(),
+            tree_creator_or_delegate: self.tree_creator_or_delegate.info(),
+            collection_authority: self.collection_authority.info(),
+            collection_authority_record: self
+                .collection_authority_record
+                .as_mut()
+                .map(TestAccount::info),
+            collection_mint: self.collection_mint.info(),
+            collection_metadata: self.collection_metadata.info(),
+            collection_edition: self.collection_edition.info(),
+            bubblegum_signer: self.bubblegum_signer.info(),
+            log_wrapper: self.log_wrapper.info(),
+            compression_program: self.compression_program.info(),
+            token_metadata_program: self.token_metadata_program.info(),
+            system_program: self.system_program.info(),
+            bubblegum_program: self.bubblegum_program.info(),
+        }
+    }
+
+    fn verify(&mut self) -> VerifyCollectionAccounts<'_> {
+        VerifyCollectionAccounts {
+            tree_config: self.tree_config.info(),
+            leaf_owner: self.leaf_owner.info(),
+            leaf_delegate: self.leaf_delegate.info(),
+            merkle_tree: self.merkle_tree.info(),
+            payer: self.payer.info(),
+            tree_creator_or_delegate: self.tree_creator_or_delegate.info(),
+            collection_authority: self.collection_authority.info(),
+            collection_authority_record: self
+                .collection_authority_record
+                .as_mut()
+                .map(TestAccount::info),
+            collection_mint: self.collection_mint.info(),
+            collection_metadata: self.collection_metadata.info(),
+            collection_edition: self.collection_edition.info(),
+            bubblegum_signer: self.bubblegum_signer.info(),
+            log_wrapper: self.log_wrapper.info(),
+            compression_program: self.compression_program.info(),
+            token_metadata_program: self.token_metadata_program.info(),
+            system_program: self.system_program.info(),
+            bubblegum_program: self.bubblegum_program.info(),
+            proof: vec![self.proof.info()],
+        }
+    }
+
+    fn mint_and_verify(&mut self) -> Result<()> {
+        bubblegum::mint_to_collection(bubblegum_metadata_args(), self.mint(), None)?;
+        bubblegum::verify_collection(
+            [1; 32],
+            [2; 32],
+            [3; 32],
+            0,
+            0,
+            bubblegum_metadata_args(),
+            self.verify(),
+            None,
+        )
+    }
+}
+
+#[test]
+fn bubblegum_collection_instructions_stand_the_program_in_for_a_missing_record() {
+    let mut collection = BubblegumCollection::new();
+    let (result, cpis) = record_cpis(|| collection.mint_and_verify());
+    result.unwrap();
+    for cpi in &cpis {
+        assert_eq!(cpi.program_id(), BUBBLEGUM_PROGRAM_ID);
+        assert_eq!(
+            cpi.instruction.accounts[7],
+            AccountMeta::new_readonly(BUBBLEGUM_PROGRAM_ID, false)
+        );
+        // The program account takes the place of the record.
+        assert_eq!(cpi.account_infos.len(), cpi.instruction.accounts.len());
+        assert!(cpi
+            .account_infos
+            .iter()
+            .any(|info| info.pubkey == BUBBLEGUM_PROGRAM_ID));
+        assert_privileges(cpi);
+    }
+    assert_eq!(
+        signers(&cpis[0]),
+        vec![
+            false, false, false, false, true, true, true, false, false, false, false, false, false,
+            false, false, false
+        ]
+    );
+    assert_eq!(
+        cpis[1].instruction.accounts[16..],
+        [AccountMeta::new_readonly(collection.proof.key(), false)]
+    );
+
+    let record = AccountBuilder::new().build();
+    let record_key = record.key();
+    collection.collection_authority_record = Some(record);
+    let (result, cpis) = record_cpis(|| collection.mint_and_verify());
+    result.unwrap();
+    for cpi in &cpis {
+        assert_eq!(
+            cpi.instruction.accounts[7],
+            AccountMeta::new_readonly(record_key, false)
+        );
+        assert_eq!(cpi.account_infos[7].pubkey, record_key);
+        assert_privileges(cpi);
+    }
+}
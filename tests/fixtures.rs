@@ -0,0 +1,85 @@
+use {
+    borsh::BorshSerialize,
+    hpl_utils::{
+        assert_token_program,
+        fixtures::{self, AccountBuilder},
+        mpl_token_metadata::state::{Collection, TokenStandard},
+        solana_program::{program_pack::Pack, pubkey::Pubkey},
+        validate_collection_creator, BpfWriter, MetadataReader, ValidateCollectionCreatorOutput,
+    },
+    spl_token::state::Account as TokenAccount,
+};
+
+#[test]
+fn metadata_fixture_validates_collection_and_creator() {
+    let mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let collection = Pubkey::new_unique();
+    let mut metadata = fixtures::nft_metadata(mint, creator);
+    metadata.collection = Some(Collection {
+        verified: true,
+        key: collection,
+    });
+    let account = fixtures::metadata(&metadata).build();
+
+    let reader = MetadataReader::new(account.data()).unwrap();
+    assert_eq!(reader.mint(), mint);
+    assert_eq!(reader.name().unwrap().trim_end_matches('\0'), "Test NFT");
+    assert_eq!(
+        reader.token_standard().unwrap(),
+        Some(TokenStandard::NonFungible)
+    );
+    assert!(matches!(
+        validate_collection_creator(&reader, &[collection], &[]).unwrap(),
+        ValidateCollectionCreatorOutput::Collection { address } if address == collection
+    ));
+    assert!(matches!(
+        validate_collection_creator(&reader, &[Pubkey::new_unique()], &[creator]).unwrap(),
+        ValidateCollectionCreatorOutput::Creator { address } if address == creator
+    ));
+    assert!(validate_collection_creator(&reader, &[], &[Pubkey::new_unique()]).is_err());
+}
+
+#[test]
+fn master_edition_fixture_is_at_the_edition_pda() {
+    let mint = Pubkey::new_unique();
+    let account = fixtures::master_edition(mint, 0, Some(0)).build();
+
+    assert_eq!(account.key(), hpl_utils::pda::find_edition(&mint).0);
+    assert_eq!(account.owner(), hpl_utils::mpl_token_metadata::ID);
+    assert_eq!(account.data()[9], 1);
+}
+
+#[test]
+fn token_fixtures_are_owned_by_their_program() {
+    let owner = Pubkey::new_unique();
+    let mut mint = fixtures::mint(Some(owner), 1, 0).build();
+    let mut token_2022_mint = fixtures::mint(None, 0, 0).owner(spl_token_2022::ID).build();
+    let mut spl_token_program = fixtures::program(spl_token::ID).build();
+
+    let token = fixtures::token_account(mint.key(), owner, 1).build();
+    let account = TokenAccount::unpack(token.data()).unwrap();
+    assert_eq!(
+        (account.mint, account.owner, account.amount),
+        (mint.key(), owner, 1)
+    );
+
+    let program_info = spl_token_program.info();
+    assert!(assert_token_program(&mint.info(), &program_info).is_ok());
+    assert!(assert_token_program(&token_2022_mint.info(), &program_info).is_err());
+}
+
+#[test]
+fn bpf_writer_serializes_into_account_data() {
+    let mut account = AccountBuilder::new().data(vec![0; 16]).writable().build();
+    let value = (42u64, [1u8; 4]);
+
+    {
+        let info = account.info();
+        let mut data = info.try_borrow_mut_data().unwrap();
+        value.serialize(&mut BpfWriter::new(&mut data[..])).unwrap();
+    }
+
+    assert_eq!(&account.data()[..12], &value.try_to_vec().unwrap()[..]);
+    assert_eq!(&account.data()[12..], &[0; 4]);
+}
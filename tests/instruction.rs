@@ -0,0 +1,79 @@
+use hpl_utils::{
+    instruction::TransferKeys,
+    mpl_token_metadata::{self, instruction::TransferArgs},
+    solana_program::{pubkey::Pubkey, system_program, sysvar},
+};
+
+fn transfer_keys() -> TransferKeys {
+    TransferKeys {
+        source_token_account: Pubkey::new_unique(),
+        source_token_account_owner: Pubkey::new_unique(),
+        destination_token_account: Pubkey::new_unique(),
+        destination_token_account_owner: Pubkey::new_unique(),
+        token_mint: Pubkey::new_unique(),
+        token_metadata: Pubkey::new_unique(),
+        token_edition: None,
+        source_token_account_record: None,
+        destination_token_account_record: None,
+        authority: Pubkey::new_unique(),
+        payer: Pubkey::new_unique(),
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: Pubkey::new_unique(),
+        sysvar_instructions: sysvar::instructions::ID,
+        authorization_rules_program: None,
+        authorization_rules: None,
+    }
+}
+
+fn transfer_args() -> TransferArgs {
+    TransferArgs::V1 {
+        amount: 1,
+        authorization_data: None,
+    }
+}
+
+#[test]
+fn transfer_keeps_token_metadata_account_order() {
+    let keys = transfer_keys();
+    let (instruction, _) = keys.instruction(transfer_args()).unwrap();
+
+    assert_eq!(instruction.program_id, mpl_token_metadata::ID);
+    assert_eq!(instruction.accounts[0].pubkey, keys.source_token_account);
+    assert_eq!(
+        instruction.accounts[2].pubkey,
+        keys.destination_token_account
+    );
+    assert_eq!(instruction.accounts[4].pubkey, keys.token_mint);
+    assert_eq!(instruction.accounts[5].pubkey, keys.token_metadata);
+    assert!(instruction.accounts[9].is_signer);
+    assert_eq!(instruction.accounts[9].pubkey, keys.authority);
+}
+
+#[test]
+fn missing_optional_accounts_are_program_placeholders() {
+    let (instruction, _) = transfer_keys().instruction(transfer_args()).unwrap();
+
+    for index in [6, 7, 8, 15, 16] {
+        assert_eq!(instruction.accounts[index].pubkey, mpl_token_metadata::ID);
+        assert!(!instruction.accounts[index].is_writable);
+    }
+}
+
+#[test]
+fn cpi_accounts_end_with_token_metadata() {
+    let keys = TransferKeys {
+        token_edition: Some(Pubkey::new_unique()),
+        ..transfer_keys()
+    };
+    let (instruction, accounts) = keys.instruction(transfer_args()).unwrap();
+
+    assert_eq!(accounts.len(), instruction.accounts.len() + 1);
+    assert_eq!(
+        accounts[..instruction.accounts.len()],
+        instruction.accounts[..]
+    );
+    let program = accounts.last().unwrap();
+    assert_eq!(program.pubkey, mpl_token_metadata::ID);
+    assert!(!program.is_signer && !program.is_writable);
+}
@@ -0,0 +1,264 @@
+use hpl_utils::{
+    merkle_tree::{
+        hash_leaf, hash_pair, verify_merkle, verify_merkle_indexed, verify_merkle_leaf,
+        verify_merkle_slice, verify_merkle_versioned, verify_multiproof, Leaf, MerkleTree,
+        MerkleVersion, Node, ProofView,
+    },
+    solana_program::keccak::hashv,
+};
+
+fn leaves(count: usize) -> Vec<[u8; 32]> {
+    (0..count)
+        .map(|i| hashv(&[&(i as u64).to_le_bytes()]).0)
+        .collect()
+}
+
+#[test]
+fn every_leaf_proves_against_root() {
+    for count in 1..=17 {
+        let leaves = leaves(count);
+        let tree = MerkleTree::new(leaves.clone()).unwrap();
+        for (index, leaf) in leaves.into_iter().enumerate() {
+            let proof = tree.proof(index).unwrap();
+            assert!(
+                verify_merkle(proof, tree.root(), leaf),
+                "leaf {index} of {count}"
+            );
+        }
+    }
+}
+
+#[test]
+fn single_leaf_is_root() {
+    let leaves = leaves(1);
+    let tree = MerkleTree::new(leaves.clone()).unwrap();
+    assert_eq!(tree.root(), leaves[0]);
+    assert!(tree.proof(0).unwrap().is_empty());
+}
+
+#[test]
+fn trees_need_a_leaf() {
+    assert_eq!(MerkleTree::new(vec![]), None);
+    assert_eq!(
+        MerkleTree::from_data::<[u8; 32]>(MerkleVersion::V2, &[]),
+        None
+    );
+    assert_eq!(MerkleTree::from_data(MerkleVersion::V1, &[[0; 31]]), None);
+}
+
+#[test]
+fn two_leaves_hash_into_root() {
+    let leaves = leaves(2);
+    let tree = MerkleTree::new(leaves.clone()).unwrap();
+    assert_eq!(tree.root(), hash_pair(&leaves[0], &leaves[1]));
+    assert_eq!(tree.proof(0).unwrap(), vec![leaves[1]]);
+}
+
+#[test]
+fn rejects_foreign_leaf_and_tampered_proof() {
+    let tree = MerkleTree::new(leaves(8)).unwrap();
+    let mut proof = tree.proof(3).unwrap();
+    let leaf = tree.leaf(3).unwrap();
+
+    assert!(!verify_merkle(proof.clone(), tree.root(), [0xab; 32]));
+
+    proof[1][0] ^= 1;
+    assert!(!verify_merkle(proof, tree.root(), leaf));
+}
+
+#[test]
+fn leaves_keep_insertion_order() {
+    let leaves = leaves(5);
+    let tree = MerkleTree::new(leaves.clone()).unwrap();
+    assert_eq!(tree.leaves().collect::<Vec<_>>(), leaves);
+    assert_eq!(tree.position(&leaves[4]), Some(4));
+    assert_eq!(tree.proof(5), None);
+}
+
+#[test]
+fn v2_leaves_are_domain_separated() {
+    let data: Vec<Vec<u8>> = (0..6u8).map(|i| vec![i; 40]).collect();
+    let tree = MerkleTree::from_data(MerkleVersion::V2, &data).unwrap();
+
+    for (index, item) in data.iter().enumerate() {
+        let proof = tree.proof(index).unwrap();
+        assert!(verify_merkle_versioned(
+            MerkleVersion::V2,
+            proof.clone(),
+            tree.root(),
+            item
+        ));
+        assert_eq!(tree.leaf(index), Some(hash_leaf(&[item]).0));
+
+        let typed: Vec<Node> = proof.into_iter().map(Node).collect();
+        assert!(verify_merkle_leaf(
+            &typed,
+            Node(tree.root()),
+            hash_leaf(&[item])
+        ));
+    }
+
+    // An internal node presented as leaf data does not prove as a leaf.
+    let proof = tree.proof(0).unwrap();
+    let node_preimage = [tree.leaf(0).unwrap(), proof[0]].concat();
+    assert!(!verify_merkle_versioned(
+        MerkleVersion::V2,
+        proof[1..].to_vec(),
+        tree.root(),
+        &node_preimage
+    ));
+}
+
+#[test]
+fn v1_roots_keep_validating() {
+    let leaves = leaves(7);
+    let tree = MerkleTree::new(leaves.clone()).unwrap();
+    assert_eq!(
+        MerkleTree::from_data(MerkleVersion::V1, &leaves).as_ref(),
+        Some(&tree)
+    );
+
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof = tree.proof(index).unwrap();
+        assert!(verify_merkle_versioned(
+            MerkleVersion::V1,
+            proof.clone(),
+            tree.root(),
+            leaf
+        ));
+        assert!(!verify_merkle_versioned(
+            MerkleVersion::V2,
+            proof,
+            tree.root(),
+            leaf
+        ));
+    }
+    assert!(!verify_merkle_versioned(
+        MerkleVersion::V1,
+        vec![],
+        tree.root(),
+        &[0; 31]
+    ));
+    assert_eq!(MerkleVersion::V1.leaf(&leaves[0]), Some(Leaf(leaves[0])));
+}
+
+#[test]
+fn multiproof_proves_any_subset() {
+    for count in 1..=9 {
+        let tree = MerkleTree::new(leaves(count)).unwrap();
+        for mask in 0u32..(1 << count) {
+            let indices: Vec<usize> = (0..count).filter(|i| mask & (1 << i) != 0).collect();
+            let multiproof = tree.multiproof(&indices).unwrap();
+            assert!(
+                verify_multiproof(
+                    &multiproof.proof,
+                    &multiproof.proof_flags,
+                    tree.root(),
+                    &multiproof.leaves
+                ),
+                "indices {indices:?} of {count}"
+            );
+        }
+    }
+}
+
+#[test]
+fn multiproof_shares_siblings() {
+    let tree = MerkleTree::new(leaves(8)).unwrap();
+    let multiproof = tree.multiproof(&[0, 1, 2, 3]).unwrap();
+    // The four leaves fill a whole subtree, so only its sibling is needed.
+    assert_eq!(multiproof.proof.len(), 1);
+    assert_eq!(multiproof.proof_flags.len(), 4);
+}
+
+#[test]
+fn multiproof_rejects_bad_input() {
+    let tree = MerkleTree::new(leaves(8)).unwrap();
+    assert_eq!(tree.multiproof(&[1, 1]), None);
+    assert_eq!(tree.multiproof(&[8]), None);
+
+    let multiproof = tree.multiproof(&[2, 5]).unwrap();
+    let mut leaves = multiproof.leaves.clone();
+    leaves[0] = [0xab; 32];
+    assert!(!verify_multiproof(
+        &multiproof.proof,
+        &multiproof.proof_flags,
+        tree.root(),
+        &leaves
+    ));
+    assert!(!verify_multiproof(
+        &multiproof.proof[1..],
+        &multiproof.proof_flags,
+        tree.root(),
+        &multiproof.leaves
+    ));
+}
+
+#[test]
+fn proof_view_reads_instruction_bytes() {
+    let tree = MerkleTree::new(leaves(11)).unwrap();
+    let proof = tree.proof(6).unwrap();
+    let leaf = tree.leaf(6).unwrap();
+    assert!(verify_merkle_slice(&proof, tree.root(), leaf));
+
+    let bytes = proof.concat();
+    let view = ProofView::new(&bytes).unwrap();
+    assert_eq!(view.len(), proof.len());
+    assert_eq!(view.get(1), Some(&proof[1]));
+    assert_eq!(view.get(proof.len()), None);
+    assert_eq!(view.get(usize::MAX / 32), None);
+    assert_eq!(view.get(usize::MAX), None);
+    assert!(view.verify(tree.root(), leaf));
+    assert!(ProofView::new(&bytes[1..]).is_none());
+
+    let mut instruction_data = (proof.len() as u32).to_le_bytes().to_vec();
+    instruction_data.extend_from_slice(&bytes);
+    instruction_data.extend_from_slice(&[7, 7]);
+    let (view, rest) = ProofView::from_prefixed(&instruction_data).unwrap();
+    assert!(view.verify(tree.root(), leaf));
+    assert_eq!(rest, &[7, 7]);
+    assert!(ProofView::from_prefixed(&instruction_data[..20]).is_none());
+}
+
+/// Positional tree without node prefixes, as built by SPL account compression.
+fn indexed_tree(leaves: &[[u8; 32]]) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves.to_vec()];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| hashv(&[&pair[0], &pair[1]]).0)
+            .collect();
+        levels.push(level);
+    }
+    levels
+}
+
+#[test]
+fn indexed_proofs_follow_leaf_position() {
+    let leaves = leaves(8);
+    let levels = indexed_tree(&leaves);
+    let root = levels.last().unwrap()[0];
+
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof: Vec<[u8; 32]> = levels[..levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| level[(index >> depth) ^ 1])
+            .collect();
+        assert!(verify_merkle_indexed(&proof, root, *leaf, index as u32));
+        assert!(ProofView::new(&proof.concat())
+            .unwrap()
+            .verify_indexed(root, *leaf, index as u32));
+
+        let wrong_index = (index as u32) ^ 1;
+        assert!(!verify_merkle_indexed(&proof, root, *leaf, wrong_index));
+        assert!(!verify_merkle_indexed(
+            &proof,
+            root,
+            *leaf,
+            index as u32 + 8
+        ));
+    }
+}
@@ -0,0 +1,259 @@
+use hpl_utils::{
+    fixtures::{self, AccountBuilder, TestAccount},
+    mpl_token_metadata::{
+        instruction::{
+            DelegateArgs, LockArgs, MetadataDelegateRole, TransferArgs, VerificationArgs,
+        },
+        state::{Collection, TokenStandard},
+    },
+    pda::{find_delegate_record, find_token_record},
+    solana_program::{pubkey::Pubkey, system_program, sysvar},
+    test_support::assert_error,
+    DelegateAccounts, LockAccounts, TransferAccounts, UtilErrors, VerifyAccounts,
+};
+
+/// A pNFT held in `token`, with every Token Metadata account at its PDA,
+/// and the other accounts its instructions take.
+struct Pnft {
+    mint: TestAccount,
+    metadata: TestAccount,
+    edition: TestAccount,
+    token: TestAccount,
+    token_record: TestAccount,
+    update_authority: TestAccount,
+    payer: TestAccount,
+    token_program: TestAccount,
+    system_program: TestAccount,
+    sysvar_instructions: TestAccount,
+}
+
+impl Pnft {
+    fn new() -> Self {
+        let update_authority = AccountBuilder::new().signer().build();
+        let mint = fixtures::mint(None, 1, 0).build();
+        let mut metadata = fixtures::nft_metadata(mint.key(), update_authority.key());
+        metadata.token_standard = Some(TokenStandard::ProgrammableNonFungible);
+        let token = fixtures::token_account(mint.key(), Pubkey::new_unique(), 1)
+            .writable()
+            .build();
+        Self {
+            metadata: fixtures::metadata(&metadata).build(),
+            edition: fixtures::master_edition(mint.key(), 0, Some(0)).build(),
+            token_record: token_record(mint.key(), token.key()),
+            mint,
+            token,
+            update_authority,
+            payer: AccountBuilder::new().signer().writable().build(),
+            token_program: fixtures::program(spl_token::ID).build(),
+            system_program: fixtures::program(system_program::ID).build(),
+            sysvar_instructions: fixtures::program(sysvar::instructions::ID).build(),
+        }
+    }
+
+    fn lock(&mut self) -> LockAccounts<'_> {
+        LockAccounts {
+            authority: self.update_authority.info(),
+            token_mint: self.mint.info(),
+            token_account: self.token.info(),
+            token_account_owner: None,
+            token_metadata: self.metadata.info(),
+            token_edition: Some(self.edition.info()),
+            token_record: Some(self.token_record.info()),
+            payer: self.payer.info(),
+            system_program: self.system_program.info(),
+            sysvar_instructions: self.sysvar_instructions.info(),
+            token_program: self.token_program.info(),
+            authorization_rules_program: None,
+            authorization_rules: None,
+        }
+    }
+}
+
+fn token_record(mint: Pubkey, token: Pubkey) -> TestAccount {
+    AccountBuilder::new()
+        .key(find_token_record(&mint, &token).0)
+        .writable()
+        .build()
+}
+
+#[test]
+fn lock_requires_the_token_record_of_pnfts() {
+    let mut pnft = Pnft::new();
+    let mut other_token_record = token_record(pnft.mint.key(), Pubkey::new_unique());
+    let mut lock = pnft.lock();
+    assert!(lock.check().is_ok());
+
+    lock.token_record = None;
+    assert_error(
+        lock.invoke_checked(
+            LockArgs::V1 {
+                authorization_data: None,
+            },
+            None,
+        ),
+        UtilErrors::MissingTokenRecord,
+    );
+
+    lock.token_record = Some(other_token_record.info());
+    assert_error(lock.check(), UtilErrors::InvalidPda);
+}
+
+#[test]
+fn lock_rejects_the_edition_of_another_mint() {
+    let mut pnft = Pnft::new();
+    let mut other_edition = fixtures::master_edition(Pubkey::new_unique(), 0, Some(0)).build();
+    let mut lock = pnft.lock();
+
+    lock.token_edition = Some(other_edition.info());
+    assert_error(lock.check(), UtilErrors::InvalidPda);
+}
+
+#[test]
+fn checks_reject_the_metadata_of_another_mint() {
+    let mut pnft = Pnft::new();
+    let mut other = Pnft::new();
+    let mut lock = pnft.lock();
+
+    lock.token_metadata = other.metadata.info();
+    assert_error(lock.check(), UtilErrors::InvalidPda);
+}
+
+#[test]
+fn transfer_rejects_swapped_token_records() {
+    let mut pnft = Pnft::new();
+    let destination_owner = Pubkey::new_unique();
+    let mut destination = fixtures::token_account(pnft.mint.key(), destination_owner, 0)
+        .writable()
+        .build();
+    let mut destination_record = token_record(pnft.mint.key(), destination.key());
+    let mut destination_owner = AccountBuilder::new().key(destination_owner).build();
+    let mut source_owner = AccountBuilder::new().signer().build();
+    let mut ata_program = fixtures::program(Pubkey::new_unique()).build();
+
+    let source_owner = source_owner.info();
+    let mut transfer = TransferAccounts {
+        source_token_account: pnft.token.info(),
+        source_token_account_owner: source_owner.clone(),
+        destination_token_account: destination.info(),
+        destination_token_account_owner: destination_owner.info(),
+        token_mint: pnft.mint.info(),
+        token_metadata: pnft.metadata.info(),
+        token_edition: Some(pnft.edition.info()),
+        source_token_account_record: Some(destination_record.info()),
+        destination_token_account_record: Some(pnft.token_record.info()),
+        authority: source_owner,
+        payer: pnft.payer.info(),
+        system_program: pnft.system_program.info(),
+        token_program: pnft.token_program.info(),
+        associated_token_program: ata_program.info(),
+        sysvar_instructions: pnft.sysvar_instructions.info(),
+        authorization_rules_program: None,
+        authorization_rules: None,
+    };
+    assert_error(
+        transfer.invoke_checked(
+            TransferArgs::V1 {
+                amount: 1,
+                authorization_data: None,
+            },
+            None,
+        ),
+        UtilErrors::InvalidPda,
+    );
+
+    std::mem::swap(
+        &mut transfer.source_token_account_record,
+        &mut transfer.destination_token_account_record,
+    );
+    assert!(transfer.check().is_ok());
+}
+
+#[test]
+fn delegate_requires_the_record_of_the_delegate() {
+    let mut pnft = Pnft::new();
+    let mut delegate = AccountBuilder::new().build();
+    let record_of = |delegate: Pubkey| {
+        let (key, _) = find_delegate_record(
+            &pnft.mint.key(),
+            MetadataDelegateRole::Collection,
+            &pnft.update_authority.key(),
+            &delegate,
+        );
+        AccountBuilder::new().key(key).writable().build()
+    };
+    let mut delegate_record = record_of(delegate.key());
+    let mut other_record = record_of(Pubkey::new_unique());
+
+    let args = DelegateArgs::CollectionV1 {
+        authorization_data: None,
+    };
+    let mut delegate_accounts = DelegateAccounts {
+        delegate_record: None,
+        delegate: delegate.info(),
+        metadata: pnft.metadata.info(),
+        master_edition: Some(pnft.edition.info()),
+        token_record: None,
+        mint: pnft.mint.info(),
+        token_account: pnft.token.info(),
+        authority: pnft.update_authority.info(),
+        payer: pnft.payer.info(),
+        system_program: pnft.system_program.info(),
+        sysvar_instructions: pnft.sysvar_instructions.info(),
+        token_program: pnft.token_program.info(),
+        authorization_rules_program: None,
+        authorization_rules: None,
+    };
+    assert_error(
+        delegate_accounts.check(&args),
+        UtilErrors::MissingDelegateRecord,
+    );
+
+    delegate_accounts.delegate_record = Some(other_record.info());
+    assert_error(delegate_accounts.check(&args), UtilErrors::InvalidPda);
+
+    delegate_accounts.delegate_record = Some(delegate_record.info());
+    assert!(delegate_accounts.check(&args).is_ok());
+}
+
+#[test]
+fn verify_collection_requires_the_collection_master_edition() {
+    let mut collection = Pnft::new();
+    let mut item = Pnft::new();
+    let mut metadata = fixtures::nft_metadata(item.mint.key(), item.update_authority.key());
+    metadata.collection = Some(Collection {
+        verified: false,
+        key: collection.mint.key(),
+    });
+    let mut item_metadata = fixtures::metadata(&metadata).build();
+
+    let mut verify = VerifyAccounts {
+        authority: collection.update_authority.info(),
+        delegate_record: None,
+        metadata: item_metadata.info(),
+        collection_mint: Some(collection.mint.info()),
+        collection_metadata: Some(collection.metadata.info()),
+        collection_master_edition: None,
+        system_program: collection.system_program.info(),
+        sysvar_instructions: collection.sysvar_instructions.info(),
+    };
+    assert!(verify.check(&VerificationArgs::CreatorV1).is_ok());
+    assert_error(
+        verify.check(&VerificationArgs::CollectionV1),
+        UtilErrors::MissingEdition,
+    );
+
+    verify.collection_master_edition = Some(item.edition.info());
+    assert_error(
+        verify.check(&VerificationArgs::CollectionV1),
+        UtilErrors::InvalidPda,
+    );
+
+    verify.collection_master_edition = Some(collection.edition.info());
+    assert!(verify.check(&VerificationArgs::CollectionV1).is_ok());
+
+    verify.collection_metadata = Some(item.metadata.info());
+    assert_error(
+        verify.check(&VerificationArgs::CollectionV1),
+        UtilErrors::InvalidPda,
+    );
+}
@@ -0,0 +1,146 @@
+use hpl_utils::{
+    fixtures,
+    mpl_token_metadata::{
+        state::{Collection, Key, Metadata, UseMethod, Uses},
+        utils::{meta_deser_unchecked, puff_out_data_fields},
+    },
+    solana_program::pubkey::Pubkey,
+    MetadataReader,
+};
+
+fn nft() -> Metadata {
+    let mut metadata = fixtures::nft_metadata(Pubkey::new_unique(), Pubkey::new_unique());
+    metadata.collection = Some(Collection {
+        verified: true,
+        key: Pubkey::new_unique(),
+    });
+    metadata.uses = Some(Uses {
+        use_method: UseMethod::Multiple,
+        remaining: 3,
+        total: 5,
+    });
+    metadata
+}
+
+/// The account data `fixtures::metadata` lays `metadata` out in.
+fn metadata_data(metadata: &Metadata) -> Vec<u8> {
+    fixtures::metadata(metadata).build().data().to_vec()
+}
+
+/// Offset of the optional `token_standard`, `collection` and `uses` tail in
+/// `metadata_data(metadata)`.
+fn optional_tail_offset(metadata: &Metadata) -> usize {
+    let mut metadata = metadata.clone();
+    puff_out_data_fields(&mut metadata);
+    // Token Metadata 1.13.2 is still on borsh 0.9.
+    #[allow(deprecated)]
+    hpl_utils::solana_program::borsh0_9::get_instance_packed_len(&(
+        metadata.key,
+        metadata.update_authority,
+        metadata.mint,
+        metadata.data,
+        metadata.primary_sale_happened,
+        metadata.is_mutable,
+        metadata.edition_nonce,
+    ))
+    .unwrap()
+}
+
+/// Checks the reader decodes `data` the way Token Metadata does.
+fn assert_reads_like_token_metadata(data: &[u8]) {
+    let expected = meta_deser_unchecked(&mut &data[..]).unwrap();
+    let reader = MetadataReader::new(data).unwrap();
+    assert_eq!(reader.edition_nonce().unwrap(), expected.edition_nonce);
+    assert_eq!(reader.token_standard().unwrap(), expected.token_standard);
+    assert_eq!(reader.collection().unwrap(), expected.collection);
+    assert_eq!(reader.uses().unwrap(), expected.uses);
+}
+
+/// Checks `token_standard`, `collection` and `uses` all read as `None`.
+fn assert_no_optional_tail(data: &[u8]) {
+    let reader = MetadataReader::new(data).unwrap();
+    assert_eq!(reader.token_standard().unwrap(), None);
+    assert_eq!(reader.collection().unwrap(), None);
+    assert_eq!(reader.uses().unwrap(), None);
+}
+
+#[test]
+fn reads_uses_and_the_edition_nonce() {
+    let mut metadata = nft();
+    let data = metadata_data(&metadata);
+    let reader = MetadataReader::new(&data).unwrap();
+    assert_eq!(reader.edition_nonce().unwrap(), metadata.edition_nonce);
+    assert_eq!(reader.token_standard().unwrap(), metadata.token_standard);
+    assert_eq!(reader.collection().unwrap(), metadata.collection);
+    assert_eq!(reader.uses().unwrap(), metadata.uses);
+    assert_reads_like_token_metadata(&data);
+
+    metadata.edition_nonce = None;
+    metadata.uses = None;
+    let data = metadata_data(&metadata);
+    let reader = MetadataReader::new(&data).unwrap();
+    assert_eq!(reader.edition_nonce().unwrap(), None);
+    assert_eq!(reader.collection().unwrap(), metadata.collection);
+    assert_eq!(reader.uses().unwrap(), None);
+    assert_reads_like_token_metadata(&data);
+}
+
+#[test]
+fn a_malformed_optional_tail_reads_as_none() {
+    let metadata = nft();
+    let tail = optional_tail_offset(&metadata);
+    let corruptions = [
+        (tail, 2),      // token standard tag
+        (tail + 1, 6),  // token standard
+        (tail + 2, 2),  // collection tag
+        (tail + 3, 2),  // collection verified
+        (tail + 36, 2), // uses tag
+        (tail + 37, 3), // use method
+    ];
+    for (offset, byte) in corruptions {
+        let mut data = metadata_data(&metadata);
+        data[offset] = byte;
+        assert_no_optional_tail(&data);
+        assert_reads_like_token_metadata(&data);
+    }
+}
+
+#[test]
+fn a_truncated_optional_tail_reads_as_none() {
+    let metadata = nft();
+    let tail = optional_tail_offset(&metadata);
+    let data = metadata_data(&metadata);
+    for len in [tail, tail + 1, tail + 2, tail + 20, tail + 36, tail + 50] {
+        assert_no_optional_tail(&data[..len]);
+        assert_reads_like_token_metadata(&data[..len]);
+    }
+
+    // Cut off right after `uses`, the tail is whole.
+    let data = &data[..tail + 54];
+    let reader = MetadataReader::new(data).unwrap();
+    assert_eq!(reader.uses().unwrap(), metadata.uses);
+    assert_reads_like_token_metadata(data);
+}
+
+#[test]
+fn malformed_fields_before_the_optional_tail_are_errors() {
+    let metadata = nft();
+    let tail = optional_tail_offset(&metadata);
+    let data = metadata_data(&metadata);
+
+    let mut wrong_key = data.clone();
+    wrong_key[0] = Key::MasterEditionV2 as u8;
+    assert!(MetadataReader::new(&wrong_key).is_err());
+    assert!(MetadataReader::new(&data[..64]).is_err());
+
+    let reader = MetadataReader::new(&data[..80]).unwrap();
+    assert!(reader.name().is_err());
+    assert!(reader.token_standard().is_err());
+
+    let mut bad_edition_nonce = data.clone();
+    bad_edition_nonce[tail - 2] = 2;
+    let reader = MetadataReader::new(&bad_edition_nonce).unwrap();
+    assert!(reader.edition_nonce().is_err());
+    assert!(reader.token_standard().is_err());
+    assert!(meta_deser_unchecked(&mut &bad_edition_nonce[..]).is_err());
+}
@@ -0,0 +1,672 @@
+//! Runs every Token Metadata wrapper against the Token Metadata program,
+//! through a native test program that makes each instruction it receives
+//! again with the matching wrapper.
+
+use {
+    hpl_utils::{
+        instruction::{
+            BurnKeys, CreateKeys, DelegateKeys, LockKeys, MintKeys, TransferKeys, UpdateKeys,
+            VerifyKeys,
+        },
+        mpl_token_metadata::{
+            self,
+            instruction::MetadataInstruction,
+            state::{Creator, TokenMetadataAccount, TokenRecord, TokenStandard, TokenState},
+        },
+        pda::{find_edition, find_metadata, find_token_record},
+        program_test::{process_instructions, start_with_token_metadata},
+        solana_program::{
+            account_info::{next_account_info, AccountInfo},
+            entrypoint::ProgramResult,
+            instruction::{AccountMeta, Instruction},
+            program_error::ProgramError,
+            program_pack::Pack,
+            pubkey,
+            pubkey::Pubkey,
+            system_program, sysvar,
+        },
+        test_support::program_error,
+        AssetData, BurnAccounts, BurnArgs, CreateAccounts, CreateArgs, DelegateAccounts,
+        DelegateArgs, LockAccounts, LockArgs, MetadataReader, MintAccounts, MintArgs, PrintSupply,
+        RevokeAccounts, RevokeArgs, TransferAccounts, TransferArgs, UnlockAccounts, UnlockArgs,
+        UpdateAccounts, UpdateArgs, VerificationArgs, VerifyAccounts,
+    },
+    solana_program_test::{processor, ProgramTest, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+    spl_token::state::{Account as TokenAccount, AccountState},
+};
+
+const PROGRAM_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+const ATA_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// The accounts of a Token Metadata instruction, in Token Metadata order,
+/// with its program id standing in for omitted optional accounts.
+struct Accounts<'a, 'info>(std::slice::Iter<'a, AccountInfo<'info>>);
+
+impl<'info> Accounts<'_, 'info> {
+    fn next(&mut self) -> Result<AccountInfo<'info>, ProgramError> {
+        next_account_info(&mut self.0).cloned()
+    }
+
+    fn next_optional(&mut self) -> Result<Option<AccountInfo<'info>>, ProgramError> {
+        let account = self.next()?;
+        Ok(Some(account).filter(|account| *account.key != mpl_token_metadata::ID))
+    }
+}
+
+fn into_program_result(result: hpl_utils::prelude::Result<()>) -> ProgramResult {
+    result.map_err(program_error)
+}
+
+fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let mut accounts = Accounts(accounts.iter());
+    // Token Metadata 1.13.2 is still on borsh 0.9.
+    #[allow(deprecated)]
+    let instruction: MetadataInstruction =
+        hpl_utils::solana_program::borsh0_9::try_from_slice_unchecked(data)
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let result = match instruction {
+        MetadataInstruction::Create(args) => {
+            let mut create = CreateAccounts {
+                metadata: accounts.next()?,
+                master_edition: accounts.next_optional()?,
+                mint: accounts.next()?,
+                authority: accounts.next()?,
+                payer: accounts.next()?,
+                update_authority: accounts.next()?,
+                system_program: accounts.next()?,
+                sysvar_instructions: accounts.next()?,
+                spl_token_program: accounts.next()?,
+                initialize_mint: false,
+                update_authority_as_signer: false,
+            };
+            create.initialize_mint = create.mint.is_signer;
+            create.update_authority_as_signer = create.update_authority.is_signer;
+            create.invoke_checked(args, None)
+        }
+        MetadataInstruction::Mint(args) => MintAccounts {
+            token: accounts.next()?,
+            token_owner: accounts.next_optional()?,
+            metadata: accounts.next()?,
+            master_edition: accounts.next_optional()?,
+            token_record: accounts.next_optional()?,
+            mint: accounts.next()?,
+            authority: accounts.next()?,
+            delegate_record: accounts.next_optional()?,
+            payer: accounts.next()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+            spl_token_program: accounts.next()?,
+            spl_ata_program: accounts.next()?,
+            authorization_rules_program: accounts.next_optional()?,
+            authorization_rules: accounts.next_optional()?,
+        }
+        .invoke_checked(args, None),
+        MetadataInstruction::Burn(args) => BurnAccounts {
+            authority: accounts.next()?,
+            collection_metadata: accounts.next_optional()?,
+            metadata: accounts.next()?,
+            edition: accounts.next_optional()?,
+            mint: accounts.next()?,
+            token: accounts.next()?,
+            master_edition: accounts.next_optional()?,
+            master_edition_mint: accounts.next_optional()?,
+            master_edition_token: accounts.next_optional()?,
+            edition_marker: accounts.next_optional()?,
+            token_record: accounts.next_optional()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+            spl_token_program: accounts.next()?,
+        }
+        .invoke_checked(args, None),
+        MetadataInstruction::Update(args) => UpdateAccounts {
+            authority: accounts.next()?,
+            delegate_record: accounts.next_optional()?,
+            token: accounts.next_optional()?,
+            mint: accounts.next()?,
+            metadata: accounts.next()?,
+            edition: accounts.next_optional()?,
+            payer: accounts.next()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+            authorization_rules_program: accounts.next_optional()?,
+            authorization_rules: accounts.next_optional()?,
+        }
+        .invoke_checked(args, None),
+        MetadataInstruction::Transfer(args) => TransferAccounts {
+            source_token_account: accounts.next()?,
+            source_token_account_owner: accounts.next()?,
+            destination_token_account: accounts.next()?,
+            destination_token_account_owner: accounts.next()?,
+            token_mint: accounts.next()?,
+            token_metadata: accounts.next()?,
+            token_edition: accounts.next_optional()?,
+            source_token_account_record: accounts.next_optional()?,
+            destination_token_account_record: accounts.next_optional()?,
+            authority: accounts.next()?,
+            payer: accounts.next()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+            token_program: accounts.next()?,
+            associated_token_program: accounts.next()?,
+            authorization_rules_program: accounts.next_optional()?,
+            authorization_rules: accounts.next_optional()?,
+        }
+        .invoke_checked(args, None),
+        MetadataInstruction::Lock(args) => LockAccounts {
+            authority: accounts.next()?,
+            token_account_owner: accounts.next_optional()?,
+            token_account: accounts.next()?,
+            token_mint: accounts.next()?,
+            token_metadata: accounts.next()?,
+            token_edition: accounts.next_optional()?,
+            token_record: accounts.next_optional()?,
+            payer: accounts.next()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+            token_program: accounts.next()?,
+            authorization_rules_program: accounts.next_optional()?,
+            authorization_rules: accounts.next_optional()?,
+        }
+        .invoke_checked(args, None),
+        MetadataInstruction::Unlock(args) => UnlockAccounts {
+            authority: accounts.next()?,
+            token_account_owner: accounts.next_optional()?,
+            token_account: accounts.next()?,
+            token_mint: accounts.next()?,
+            token_metadata: accounts.next()?,
+            token_edition: accounts.next_optional()?,
+            token_record: accounts.next_optional()?,
+            payer: accounts.next()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+            token_program: accounts.next()?,
+            authorization_rules_program: accounts.next_optional()?,
+            authorization_rules: accounts.next_optional()?,
+        }
+        .invoke_checked(args, None),
+        MetadataInstruction::Delegate(args) => DelegateAccounts {
+            delegate_record: accounts.next_optional()?,
+            delegate: accounts.next()?,
+            metadata: accounts.next()?,
+            master_edition: accounts.next_optional()?,
+            token_record: accounts.next_optional()?,
+            mint: accounts.next()?,
+            token_account: accounts.next()?,
+            authority: accounts.next()?,
+            payer: accounts.next()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+            token_program: accounts.next()?,
+            authorization_rules_program: accounts.next_optional()?,
+            authorization_rules: accounts.next_optional()?,
+        }
+        .invoke_checked(args, None),
+        MetadataInstruction::Revoke(args) => RevokeAccounts {
+            delegate_record: accounts.next_optional()?,
+            delegate: accounts.next()?,
+            metadata: accounts.next()?,
+            master_edition: accounts.next_optional()?,
+            token_record: accounts.next_optional()?,
+            mint: accounts.next()?,
+            token_account: accounts.next()?,
+            authority: accounts.next()?,
+            payer: accounts.next()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+            token_program: accounts.next()?,
+            authorization_rules_program: accounts.next_optional()?,
+            authorization_rules: accounts.next_optional()?,
+        }
+        .invoke_checked(args, None),
+        MetadataInstruction::Verify(args) => VerifyAccounts {
+            authority: accounts.next()?,
+            delegate_record: accounts.next_optional()?,
+            metadata: accounts.next()?,
+            collection_mint: accounts.next_optional()?,
+            collection_metadata: accounts.next_optional()?,
+            collection_master_edition: accounts.next_optional()?,
+            system_program: accounts.next()?,
+            sysvar_instructions: accounts.next()?,
+        }
+        .invoke_checked(args, None),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    into_program_result(result)
+}
+
+/// Sends a Token Metadata instruction to the test program instead, along
+/// with the accounts it needs to make the CPI.
+fn wrap((instruction, accounts): (Instruction, Vec<AccountMeta>)) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: instruction.data,
+    }
+}
+
+async fn start() -> ProgramTestContext {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(false);
+    program_test.add_program(
+        "hpl_utils_test",
+        PROGRAM_ID,
+        processor!(process_instruction),
+    );
+    start_with_token_metadata(program_test).await
+}
+
+async fn send(context: &mut ProgramTestContext, instruction: Instruction, signers: &[&Keypair]) {
+    process_instructions(context, &[instruction], signers)
+        .await
+        .unwrap();
+}
+
+async fn account_data(context: &mut ProgramTestContext, address: Pubkey) -> Option<Vec<u8>> {
+    let account = context.banks_client.get_account(address).await.unwrap();
+    account.map(|account| account.data)
+}
+
+async fn token_account(context: &mut ProgramTestContext, address: Pubkey) -> TokenAccount {
+    TokenAccount::unpack(&account_data(context, address).await.unwrap()).unwrap()
+}
+
+fn associated_token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), spl_token::ID.as_ref(), mint.as_ref()],
+        &ATA_PROGRAM_ID,
+    )
+    .0
+}
+
+struct Asset {
+    token_standard: TokenStandard,
+    mint: Pubkey,
+    metadata: Pubkey,
+    edition: Pubkey,
+}
+
+impl Asset {
+    fn new(mint: Pubkey, token_standard: TokenStandard) -> Self {
+        Self {
+            token_standard,
+            mint,
+            metadata: find_metadata(&mint).0,
+            edition: find_edition(&mint).0,
+        }
+    }
+
+    fn is_programmable(&self) -> bool {
+        self.token_standard == TokenStandard::ProgrammableNonFungible
+    }
+
+    fn token(&self, owner: &Pubkey) -> Pubkey {
+        associated_token_account(owner, &self.mint)
+    }
+
+    fn token_record(&self, owner: &Pubkey) -> Option<Pubkey> {
+        self.is_programmable()
+            .then(|| find_token_record(&self.mint, &self.token(owner)).0)
+    }
+
+    /// Creates the asset with `authority` as its update and mint authority,
+    /// and its only, unverified creator.
+    fn create(&self, authority: Pubkey) -> Instruction {
+        let mut asset_data = AssetData::new(
+            self.token_standard,
+            "Test NFT".to_string(),
+            "TEST".to_string(),
+            "https://example.com/nft.json".to_string(),
+        );
+        asset_data.creators = Some(vec![Creator {
+            address: authority,
+            verified: false,
+            share: 100,
+        }]);
+        let keys = CreateKeys {
+            metadata: self.metadata,
+            master_edition: Some(self.edition),
+            mint: self.mint,
+            authority,
+            payer: authority,
+            update_authority: authority,
+            system_program: system_program::ID,
+            sysvar_instructions: sysvar::instructions::ID,
+            spl_token_program: spl_token::ID,
+            initialize_mint: true,
+            update_authority_as_signer: true,
+        };
+        wrap(
+            keys.instruction(CreateArgs::V1 {
+                asset_data,
+                decimals: Some(0),
+                print_supply: Some(PrintSupply::Zero),
+            })
+            .unwrap(),
+        )
+    }
+
+    fn mint(&self, authority: Pubkey) -> Instruction {
+        let keys = MintKeys {
+            token: self.token(&authority),
+            token_owner: Some(authority),
+            metadata: self.metadata,
+            master_edition: Some(self.edition),
+            token_record: self.token_record(&authority),
+            mint: self.mint,
+            authority,
+            delegate_record: None,
+            payer: authority,
+            system_program: system_program::ID,
+            sysvar_instructions: sysvar::instructions::ID,
+            spl_token_program: spl_token::ID,
+            spl_ata_program: ATA_PROGRAM_ID,
+            authorization_rules_program: None,
+            authorization_rules: None,
+        };
+        wrap(
+            keys.instruction(MintArgs::V1 {
+                amount: 1,
+                authorization_data: None,
+            })
+            .unwrap(),
+        )
+    }
+
+    fn delegate_keys(&self, owner: Pubkey, delegate: Pubkey) -> DelegateKeys {
+        DelegateKeys {
+            delegate_record: None,
+            delegate,
+            metadata: self.metadata,
+            master_edition: Some(self.edition),
+            token_record: self.token_record(&owner),
+            mint: self.mint,
+            token_account: self.token(&owner),
+            authority: owner,
+            payer: owner,
+            system_program: system_program::ID,
+            sysvar_instructions: sysvar::instructions::ID,
+            token_program: spl_token::ID,
+            authorization_rules_program: None,
+            authorization_rules: None,
+        }
+    }
+
+    /// Approves `delegate` as the delegate that can lock the token: a
+    /// utility delegate for pNFTs, a plain SPL Token delegate otherwise.
+    fn delegate(&self, owner: Pubkey, delegate: Pubkey) -> Instruction {
+        let args = if self.is_programmable() {
+            DelegateArgs::UtilityV1 {
+                amount: 1,
+                authorization_data: None,
+            }
+        } else {
+            DelegateArgs::StandardV1 { amount: 1 }
+        };
+        wrap(
+            self.delegate_keys(owner, delegate)
+                .delegate_instruction(args)
+                .unwrap(),
+        )
+    }
+
+    fn revoke(&self, owner: Pubkey, delegate: Pubkey) -> Instruction {
+        let args = if self.is_programmable() {
+            RevokeArgs::UtilityV1
+        } else {
+            RevokeArgs::StandardV1
+        };
+        wrap(
+            self.delegate_keys(owner, delegate)
+                .revoke_instruction(args)
+                .unwrap(),
+        )
+    }
+
+    fn lock_keys(&self, owner: Pubkey, delegate: Pubkey, payer: Pubkey) -> LockKeys {
+        LockKeys {
+            authority: delegate,
+            token_mint: self.mint,
+            token_account: self.token(&owner),
+            token_account_owner: Some(owner),
+            token_metadata: self.metadata,
+            token_edition: Some(self.edition),
+            token_record: self.token_record(&owner),
+            payer,
+            system_program: system_program::ID,
+            sysvar_instructions: sysvar::instructions::ID,
+            token_program: spl_token::ID,
+            authorization_rules_program: None,
+            authorization_rules: None,
+        }
+    }
+
+    fn lock(&self, owner: Pubkey, delegate: Pubkey, payer: Pubkey) -> Instruction {
+        wrap(
+            self.lock_keys(owner, delegate, payer)
+                .lock_instruction(LockArgs::V1 {
+                    authorization_data: None,
+                })
+                .unwrap(),
+        )
+    }
+
+    fn unlock(&self, owner: Pubkey, delegate: Pubkey, payer: Pubkey) -> Instruction {
+        wrap(
+            self.lock_keys(owner, delegate, payer)
+                .unlock_instruction(UnlockArgs::V1 {
+                    authorization_data: None,
+                })
+                .unwrap(),
+        )
+    }
+
+    fn transfer(&self, owner: Pubkey, destination_owner: Pubkey) -> Instruction {
+        let keys = TransferKeys {
+            source_token_account: self.token(&owner),
+            source_token_account_owner: owner,
+            destination_token_account: self.token(&destination_owner),
+            destination_token_account_owner: destination_owner,
+            token_mint: self.mint,
+            token_metadata: self.metadata,
+            token_edition: Some(self.edition),
+            source_token_account_record: self.token_record(&owner),
+            destination_token_account_record: self.token_record(&destination_owner),
+            authority: owner,
+            payer: owner,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            associated_token_program: ATA_PROGRAM_ID,
+            sysvar_instructions: sysvar::instructions::ID,
+            authorization_rules_program: None,
+            authorization_rules: None,
+        };
+        wrap(
+            keys.instruction(TransferArgs::V1 {
+                amount: 1,
+                authorization_data: None,
+            })
+            .unwrap(),
+        )
+    }
+
+    fn burn(&self, owner: Pubkey) -> Instruction {
+        let keys = BurnKeys {
+            authority: owner,
+            collection_metadata: None,
+            metadata: self.metadata,
+            edition: Some(self.edition),
+            mint: self.mint,
+            token: self.token(&owner),
+            master_edition: None,
+            master_edition_mint: None,
+            master_edition_token: None,
+            edition_marker: None,
+            token_record: self.token_record(&owner),
+            system_program: system_program::ID,
+            sysvar_instructions: sysvar::instructions::ID,
+            spl_token_program: spl_token::ID,
+        };
+        wrap(keys.instruction(BurnArgs::V1 { amount: 1 }).unwrap())
+    }
+
+    fn update(&self, authority: Pubkey, args: UpdateArgs) -> Instruction {
+        let keys = UpdateKeys {
+            delegate_record: None,
+            token: None,
+            mint: self.mint,
+            metadata: self.metadata,
+            edition: Some(self.edition),
+            authority,
+            payer: authority,
+            system_program: system_program::ID,
+            sysvar_instructions: sysvar::instructions::ID,
+            authorization_rules_program: None,
+            authorization_rules: None,
+        };
+        wrap(keys.instruction(args).unwrap())
+    }
+
+    fn verify_creator(&self, creator: Pubkey) -> Instruction {
+        let keys = VerifyKeys {
+            authority: creator,
+            delegate_record: None,
+            metadata: self.metadata,
+            collection_mint: None,
+            collection_metadata: None,
+            collection_master_edition: None,
+            system_program: system_program::ID,
+            sysvar_instructions: sysvar::instructions::ID,
+        };
+        wrap(keys.instruction(VerificationArgs::CreatorV1).unwrap())
+    }
+
+    async fn is_locked(&self, context: &mut ProgramTestContext, owner: &Pubkey) -> bool {
+        match self.token_record(owner) {
+            Some(token_record) => {
+                let data = account_data(context, token_record).await.unwrap();
+                TokenRecord::safe_deserialize(&data).unwrap().state == TokenState::Locked
+            }
+            None => {
+                let token = token_account(context, self.token(owner)).await;
+                token.state == AccountState::Frozen
+            }
+        }
+    }
+}
+
+/// Creates and mints an asset to the context payer, then takes it through
+/// delegate, lock, unlock, revoke, transfer and burn.
+async fn lifecycle(token_standard: TokenStandard) {
+    let mut context = start().await;
+    let owner = context.payer.pubkey();
+    let mint = Keypair::new();
+    let delegate = Keypair::new();
+    let destination_owner = Keypair::new();
+    let asset = Asset::new(mint.pubkey(), token_standard);
+
+    send(&mut context, asset.create(owner), &[&mint]).await;
+    send(&mut context, asset.mint(owner), &[]).await;
+    let token = token_account(&mut context, asset.token(&owner)).await;
+    assert_eq!(
+        (token.mint, token.owner, token.amount),
+        (asset.mint, owner, 1)
+    );
+
+    send(&mut context, asset.delegate(owner, delegate.pubkey()), &[]).await;
+    let token = token_account(&mut context, asset.token(&owner)).await;
+    assert_eq!(token.delegate, Some(delegate.pubkey()).into());
+
+    send(
+        &mut context,
+        asset.lock(owner, delegate.pubkey(), owner),
+        &[&delegate],
+    )
+    .await;
+    assert!(asset.is_locked(&mut context, &owner).await);
+
+    send(
+        &mut context,
+        asset.unlock(owner, delegate.pubkey(), owner),
+        &[&delegate],
+    )
+    .await;
+    assert!(!asset.is_locked(&mut context, &owner).await);
+
+    send(&mut context, asset.revoke(owner, delegate.pubkey()), &[]).await;
+    let token = token_account(&mut context, asset.token(&owner)).await;
+    assert!(token.delegate.is_none());
+
+    send(
+        &mut context,
+        asset.transfer(owner, destination_owner.pubkey()),
+        &[],
+    )
+    .await;
+    let token = token_account(&mut context, asset.token(&destination_owner.pubkey())).await;
+    assert_eq!((token.owner, token.amount), (destination_owner.pubkey(), 1));
+
+    send(
+        &mut context,
+        asset.burn(destination_owner.pubkey()),
+        &[&destination_owner],
+    )
+    .await;
+    // Token Metadata keeps its creation fee in a burned metadata account,
+    // cleared down to an uninitialized key.
+    assert_eq!(
+        account_data(&mut context, asset.metadata).await,
+        Some(vec![0])
+    );
+    assert!(account_data(&mut context, asset.edition).await.is_none());
+    let token = account_data(&mut context, asset.token(&destination_owner.pubkey())).await;
+    assert!(token.is_none());
+}
+
+#[tokio::test]
+async fn nft_lifecycle() {
+    lifecycle(TokenStandard::NonFungible).await;
+}
+
+#[tokio::test]
+async fn pnft_lifecycle() {
+    lifecycle(TokenStandard::ProgrammableNonFungible).await;
+}
+
+#[tokio::test]
+async fn update_and_verify_creator() {
+    let mut context = start().await;
+    let authority = context.payer.pubkey();
+    let mint = Keypair::new();
+    let asset = Asset::new(mint.pubkey(), TokenStandard::NonFungible);
+    send(&mut context, asset.create(authority), &[&mint]).await;
+
+    let mut args = UpdateArgs::default_v1();
+    if let UpdateArgs::V1 {
+        primary_sale_happened,
+        ..
+    } = &mut args
+    {
+        *primary_sale_happened = Some(true);
+    }
+    send(&mut context, asset.update(authority, args), &[]).await;
+    send(&mut context, asset.verify_creator(authority), &[]).await;
+
+    let data = account_data(&mut context, asset.metadata).await.unwrap();
+    let metadata = MetadataReader::new(&data).unwrap();
+    assert!(metadata.primary_sale_happened().unwrap());
+    let creators: Vec<Creator> = metadata.creators().unwrap().unwrap().iter().collect();
+    assert_eq!(
+        creators,
+        vec![Creator {
+            address: authority,
+            verified: true,
+            share: 100,
+        }]
+    );
+}
@@ -0,0 +1,211 @@
+use hpl_utils::{
+    fixtures::{self, AccountBuilder},
+    prelude::Result,
+    reallocate, reallocate_to,
+    solana_program::{
+        entrypoint::MAX_PERMITTED_DATA_INCREASE, program_error::ProgramError, rent::Rent,
+        system_instruction, system_program,
+    },
+    test_support::{program_error, record_cpis},
+};
+
+enum Resize {
+    By(isize),
+    To { new_len: usize, zero_init: bool },
+}
+
+#[cfg(feature = "anchor")]
+fn resize_account(
+    resize: Resize,
+    account: &mut fixtures::TestAccount,
+    payer: &mut fixtures::TestAccount,
+) -> Result<()> {
+    use anchor_lang::prelude::{Program, Sysvar};
+
+    let mut rent = fixtures::rent_sysvar().build();
+    let mut system = fixtures::program(system_program::ID).build();
+    let rent_info = rent.info();
+    let system_info = system.info();
+    let rent_sysvar = Sysvar::from_account_info(&rent_info).unwrap();
+    let system_program = Program::try_from(&system_info).unwrap();
+    match resize {
+        Resize::By(len) => reallocate(
+            len,
+            account.info(),
+            payer.info(),
+            &rent_sysvar,
+            &system_program,
+        ),
+        Resize::To { new_len, zero_init } => reallocate_to(
+            new_len,
+            zero_init,
+            account.info(),
+            payer.info(),
+            &rent_sysvar,
+            &system_program,
+        ),
+    }
+}
+
+#[cfg(not(feature = "anchor"))]
+fn resize_account(
+    resize: Resize,
+    account: &mut fixtures::TestAccount,
+    payer: &mut fixtures::TestAccount,
+) -> Result<()> {
+    let mut system = fixtures::program(system_program::ID).build();
+    let rent = Rent::default();
+    match resize {
+        Resize::By(len) => reallocate(len, account.info(), payer.info(), &rent, &system.info()),
+        Resize::To { new_len, zero_init } => reallocate_to(
+            new_len,
+            zero_init,
+            account.info(),
+            payer.info(),
+            &rent,
+            &system.info(),
+        ),
+    }
+}
+
+#[test]
+fn reallocate_grows_data_and_charges_payer() {
+    let mut account = AccountBuilder::new()
+        .data(vec![7; 100])
+        .writable()
+        .rent_exempt()
+        .build();
+    let mut payer = AccountBuilder::new()
+        .lamports(1_000_000_000)
+        .signer()
+        .writable()
+        .build();
+
+    let ((), cpis) =
+        record_cpis(|| resize_account(Resize::By(200), &mut account, &mut payer).unwrap());
+
+    assert_eq!(account.data().len(), 300);
+    assert_eq!(&account.data()[..100], &[7; 100]);
+    let rent = Rent::default();
+    let expected = system_instruction::transfer(
+        &payer.key(),
+        &account.key(),
+        rent.minimum_balance(300) - rent.minimum_balance(100),
+    );
+    assert_eq!(cpis.len(), 1);
+    assert_eq!(cpis[0].instruction, expected);
+}
+
+#[test]
+fn reallocate_shrinks_data_and_refunds_payer() {
+    let rent = Rent::default();
+    let mut account = AccountBuilder::new()
+        .data(vec![7; 300])
+        .writable()
+        .rent_exempt()
+        .build();
+    let mut payer = AccountBuilder::new().signer().writable().build();
+
+    let ((), cpis) =
+        record_cpis(|| resize_account(Resize::By(-200), &mut account, &mut payer).unwrap());
+
+    assert!(cpis.is_empty());
+    assert_eq!(account.data(), &[7; 100]);
+    assert_eq!(account.lamports(), rent.minimum_balance(100));
+    assert_eq!(
+        payer.lamports(),
+        rent.minimum_balance(300) - rent.minimum_balance(100)
+    );
+}
+
+#[test]
+fn reallocate_to_zeroes_bytes_exposed_after_a_shrink() {
+    let rent = Rent::default();
+    let mut account = AccountBuilder::new()
+        .data(vec![7; 300])
+        .writable()
+        .rent_exempt()
+        .build();
+    let mut payer = AccountBuilder::new().signer().writable().build();
+
+    let ((), cpis) = record_cpis(|| {
+        let shrink = Resize::To {
+            new_len: 100,
+            zero_init: true,
+        };
+        resize_account(shrink, &mut account, &mut payer).unwrap();
+        let grow = Resize::To {
+            new_len: 300,
+            zero_init: true,
+        };
+        resize_account(grow, &mut account, &mut payer).unwrap();
+    });
+
+    assert_eq!(&account.data()[..100], &[7; 100]);
+    assert_eq!(&account.data()[100..], &[0; 200]);
+    let refund = rent.minimum_balance(300) - rent.minimum_balance(100);
+    assert_eq!(payer.lamports(), refund);
+    assert_eq!(cpis.len(), 1);
+    assert_eq!(
+        cpis[0].instruction,
+        system_instruction::transfer(&payer.key(), &account.key(), refund)
+    );
+}
+
+#[test]
+fn reallocate_to_the_current_length_does_nothing() {
+    let mut account = AccountBuilder::new()
+        .data(vec![7; 100])
+        .writable()
+        .rent_exempt()
+        .build();
+    let mut payer = AccountBuilder::new().signer().writable().build();
+    let before = account.clone();
+
+    let ((), cpis) = record_cpis(|| {
+        let resize = Resize::To {
+            new_len: 100,
+            zero_init: true,
+        };
+        resize_account(resize, &mut account, &mut payer).unwrap()
+    });
+
+    assert!(cpis.is_empty());
+    assert_eq!(account, before);
+    assert_eq!(payer.lamports(), 0);
+}
+
+#[test]
+fn reallocate_cannot_grow_past_the_instruction_limit() {
+    let mut account = AccountBuilder::new()
+        .data(vec![7; 100])
+        .writable()
+        .rent_exempt()
+        .build();
+    let mut payer = AccountBuilder::new().signer().writable().build();
+
+    let (result, _) = record_cpis(|| {
+        // Splitting the growth into steps does not get past the limit.
+        let half = MAX_PERMITTED_DATA_INCREASE as isize / 2;
+        resize_account(Resize::By(half), &mut account, &mut payer)?;
+        resize_account(Resize::By(half), &mut account, &mut payer)?;
+        resize_account(Resize::By(1), &mut account, &mut payer)
+    });
+
+    let error = program_error(result.unwrap_err());
+    assert_eq!(error, ProgramError::InvalidRealloc);
+    assert_eq!(account.data().len(), 100 + MAX_PERMITTED_DATA_INCREASE);
+
+    let grow = Resize::To {
+        new_len: 101 + MAX_PERMITTED_DATA_INCREASE,
+        zero_init: true,
+    };
+    let mut account = AccountBuilder::new()
+        .data(vec![7; 100])
+        .writable()
+        .rent_exempt()
+        .build();
+    let (result, _) = record_cpis(|| resize_account(grow, &mut account, &mut payer));
+    let error = program_error(result.unwrap_err());
+    assert_eq!(error, ProgramError::InvalidRealloc);
+}
@@ -0,0 +1,437 @@
+use {
+    hpl_utils::{
+        assert_token_program,
+        fixtures::{self, AccountBuilder, TestAccount},
+        mpl_token_metadata::state::TokenStandard,
+        solana_program::{program_option::COption, pubkey::Pubkey, system_program, sysvar},
+        test_support::assert_error,
+        AssetData, AssetGroup, AssetMetadata, AssetMetadataSource, CreateAccounts, CreateArgs,
+        MintExtensions, UpdateAccounts, UpdateArgs, UtilErrors,
+    },
+    spl_token_2022::{
+        extension::{
+            metadata_pointer::MetadataPointer, non_transferable::NonTransferable,
+            permanent_delegate::PermanentDelegate, transfer_hook::TransferHook, ExtensionType,
+            StateWithExtensionsMut,
+        },
+        state::Mint,
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+    spl_type_length_value::{state::TlvStateMut, variable_len_pack::VariableLenPack},
+};
+
+/// An initialized Token-2022 mint with `extensions`, set up by `init`.
+fn token_2022_mint(
+    extensions: &[ExtensionType],
+    init: impl FnOnce(&mut StateWithExtensionsMut<Mint>),
+) -> TestAccount {
+    let len = ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap();
+    let mut data = vec![0; len];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    init(&mut state);
+    initialize_mint(&mut state);
+    AccountBuilder::new()
+        .owner(spl_token_2022::ID)
+        .data(data)
+        .rent_exempt()
+        .build()
+}
+
+fn initialize_mint(state: &mut StateWithExtensionsMut<Mint>) {
+    state.base = Mint {
+        mint_authority: COption::None,
+        supply: 1,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+}
+
+#[test]
+fn assert_token_program_requires_the_mint_owner() {
+    let mut mint = fixtures::mint(None, 1, 0).build();
+    let mut token_2022_mint = token_2022_mint(&[], |_| {});
+    let mut stray_mint = fixtures::mint(None, 1, 0)
+        .owner(Pubkey::new_unique())
+        .build();
+    let mut spl_token = fixtures::program(spl_token::ID).build();
+    let mut token_2022 = fixtures::program(spl_token_2022::ID).build();
+    let mut not_a_token_program = fixtures::program(system_program::ID).build();
+
+    let spl_token = spl_token.info();
+    let token_2022 = token_2022.info();
+    assert!(assert_token_program(&mint.info(), &spl_token).is_ok());
+    assert!(assert_token_program(&token_2022_mint.info(), &token_2022).is_ok());
+    assert!(assert_token_program(&token_2022_mint.info(), &spl_token).is_err());
+    assert!(assert_token_program(&mint.info(), &token_2022).is_err());
+    assert!(assert_token_program(&stray_mint.info(), &spl_token).is_err());
+    assert!(assert_token_program(&mint.info(), &not_a_token_program.info()).is_err());
+}
+
+#[test]
+fn metadata_checks_reject_token_2022_mints() {
+    let mut mint = token_2022_mint(&[], |_| {});
+    let mut token_2022 = fixtures::program(spl_token_2022::ID).build();
+    let mut metadata = AccountBuilder::new().writable().build();
+    let mut master_edition = AccountBuilder::new().writable().build();
+    let mut authority = AccountBuilder::new().signer().build();
+    let mut system = fixtures::program(system_program::ID).build();
+    let mut instructions = fixtures::program(sysvar::instructions::ID).build();
+    let args = CreateArgs::V1 {
+        asset_data: AssetData::new(
+            TokenStandard::NonFungible,
+            "Test NFT".to_string(),
+            "TEST".to_string(),
+            "https://example.com/nft.json".to_string(),
+        ),
+        decimals: Some(0),
+        print_supply: None,
+    };
+
+    let authority = authority.info();
+    let mut create = CreateAccounts {
+        metadata: metadata.info(),
+        master_edition: Some(master_edition.info()),
+        mint: mint.info(),
+        authority: authority.clone(),
+        payer: authority.clone(),
+        update_authority: authority,
+        system_program: system.info(),
+        sysvar_instructions: instructions.info(),
+        spl_token_program: token_2022.info(),
+        initialize_mint: false,
+        update_authority_as_signer: false,
+    };
+    assert!(create.check(&args).is_err());
+    create.initialize_mint = true;
+    assert!(create.check(&args).is_err());
+
+    let update = UpdateAccounts {
+        delegate_record: None,
+        token: None,
+        mint: create.mint.clone(),
+        metadata: create.metadata.clone(),
+        edition: create.master_edition.clone(),
+        authority: create.authority.clone(),
+        payer: create.payer.clone(),
+        system_program: create.system_program.clone(),
+        sysvar_instructions: create.sysvar_instructions.clone(),
+        authorization_rules_program: None,
+        authorization_rules: None,
+    };
+    assert_error(
+        update.check(&UpdateArgs::default_v1()),
+        UtilErrors::InvalidTokenProgram,
+    );
+}
+
+#[test]
+fn classic_mints_have_no_extensions() {
+    let mut mint = fixtures::mint(None, 1, 0).build();
+
+    assert_eq!(
+        MintExtensions::read(&mint.info()).unwrap(),
+        MintExtensions::default()
+    );
+}
+
+#[test]
+fn reads_non_transferable_mints() {
+    let mut mint = token_2022_mint(&[ExtensionType::NonTransferable], |state| {
+        state.init_extension::<NonTransferable>(true).unwrap();
+    });
+
+    assert_eq!(
+        MintExtensions::read(&mint.info()).unwrap(),
+        MintExtensions {
+            non_transferable: true,
+            ..MintExtensions::default()
+        }
+    );
+}
+
+#[test]
+fn reads_permanent_delegates() {
+    let delegate = Pubkey::new_unique();
+    let mut mint = token_2022_mint(&[ExtensionType::PermanentDelegate], |state| {
+        let extension = state.init_extension::<PermanentDelegate>(true).unwrap();
+        extension.delegate = Some(delegate).try_into().unwrap();
+    });
+
+    assert_eq!(
+        MintExtensions::read(&mint.info()).unwrap(),
+        MintExtensions {
+            permanent_delegate: Some(delegate),
+            ..MintExtensions::default()
+        }
+    );
+}
+
+#[test]
+fn reads_transfer_hooks() {
+    let program = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let mut mint = token_2022_mint(&[ExtensionType::TransferHook], |state| {
+        let extension = state.init_extension::<TransferHook>(true).unwrap();
+        extension.program_id = Some(program).try_into().unwrap();
+        extension.authority = Some(authority).try_into().unwrap();
+    });
+
+    assert_eq!(
+        MintExtensions::read(&mint.info()).unwrap(),
+        MintExtensions {
+            transfer_hook_program: Some(program),
+            transfer_hook_authority: Some(authority),
+            ..MintExtensions::default()
+        }
+    );
+}
+
+#[test]
+fn mints_of_other_programs_are_rejected() {
+    let mut mint = fixtures::mint(None, 1, 0)
+        .owner(Pubkey::new_unique())
+        .build();
+
+    assert!(MintExtensions::read(&mint.info()).is_err());
+}
+
+/// A Token-2022 mint at `key` whose metadata pointer refers to
+/// `metadata_address`, holding `token_metadata` itself when given, and
+/// followed by the raw TLV entries `trailing_tlv`.
+fn metadata_mint(
+    key: Pubkey,
+    metadata_address: Pubkey,
+    token_metadata: Option<&TokenMetadata>,
+    trailing_tlv: &[u8],
+) -> TestAccount {
+    let mut len =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MetadataPointer])
+            .unwrap();
+    if let Some(token_metadata) = token_metadata {
+        len += 4 + token_metadata.get_packed_len().unwrap();
+    }
+    let mut data = vec![0; len + trailing_tlv.len()];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    let pointer = state.init_extension::<MetadataPointer>(true).unwrap();
+    pointer.metadata_address = Some(metadata_address).try_into().unwrap();
+    if let Some(token_metadata) = token_metadata {
+        state
+            .init_variable_len_extension(token_metadata, false)
+            .unwrap();
+    }
+    initialize_mint(&mut state);
+    data[len..].copy_from_slice(trailing_tlv);
+    AccountBuilder::new()
+        .key(key)
+        .owner(spl_token_2022::ID)
+        .data(data)
+        .rent_exempt()
+        .build()
+}
+
+/// An account of a program implementing the token-metadata interface.
+fn token_metadata_account(token_metadata: &TokenMetadata) -> TestAccount {
+    let mut data = vec![0; 12 + token_metadata.get_packed_len().unwrap()];
+    TlvStateMut::unpack(&mut data)
+        .unwrap()
+        .alloc_and_pack_variable_len_entry(token_metadata, false)
+        .unwrap();
+    AccountBuilder::new()
+        .owner(Pubkey::new_unique())
+        .data(data)
+        .rent_exempt()
+        .build()
+}
+
+fn token_metadata(mint: Pubkey) -> TokenMetadata {
+    TokenMetadata {
+        update_authority: Some(Pubkey::new_unique()).try_into().unwrap(),
+        mint,
+        name: "Token-2022 NFT".to_string(),
+        symbol: "T22".to_string(),
+        uri: "https://example.com/t22.json".to_string(),
+        additional_metadata: vec![("trait".to_string(), "value".to_string())],
+    }
+}
+
+/// The raw `GroupMemberPointer` extension, which Token-2022 0.9 cannot write.
+fn group_member_pointer_tlv(member_address: Pubkey) -> Vec<u8> {
+    let mut entry = 22u16.to_le_bytes().to_vec();
+    entry.extend(64u16.to_le_bytes());
+    entry.extend([0; 32]);
+    entry.extend(member_address.as_ref());
+    entry
+}
+
+/// The raw `TokenGroupMember` extension, which Token-2022 0.9 cannot write.
+fn group_member_tlv(mint: Pubkey, group: Pubkey, len: u16) -> Vec<u8> {
+    let mut entry = 23u16.to_le_bytes().to_vec();
+    entry.extend(len.to_le_bytes());
+    entry.extend(mint.as_ref());
+    entry.extend(group.as_ref());
+    entry.extend(1u64.to_le_bytes());
+    entry
+}
+
+fn expected_metadata(token_metadata: &TokenMetadata, group: Option<AssetGroup>) -> AssetMetadata {
+    AssetMetadata {
+        source: AssetMetadataSource::Token2022,
+        mint: token_metadata.mint,
+        name: token_metadata.name.clone(),
+        symbol: token_metadata.symbol.clone(),
+        uri: token_metadata.uri.clone(),
+        update_authority: token_metadata.update_authority.into(),
+        group,
+        creators: vec![],
+    }
+}
+
+#[test]
+fn reads_metadata_stored_in_the_mint() {
+    let key = Pubkey::new_unique();
+    let token_metadata = token_metadata(key);
+    let mut mint = metadata_mint(key, key, Some(&token_metadata), &[]);
+    let mint = mint.info();
+
+    assert_eq!(
+        AssetMetadata::load(&mint, &mint).unwrap(),
+        expected_metadata(&token_metadata, None)
+    );
+}
+
+#[test]
+fn reads_metadata_from_a_separate_account() {
+    let key = Pubkey::new_unique();
+    let token_metadata = token_metadata(key);
+    let mut metadata = token_metadata_account(&token_metadata);
+    let mut mint = metadata_mint(key, metadata.key(), None, &[]);
+
+    assert_eq!(
+        AssetMetadata::load(&mint.info(), &metadata.info()).unwrap(),
+        expected_metadata(&token_metadata, None)
+    );
+}
+
+#[test]
+fn rejects_metadata_the_mint_does_not_point_to() {
+    let key = Pubkey::new_unique();
+    let token_metadata = token_metadata(key);
+    let mut metadata = token_metadata_account(&token_metadata);
+    let mut other_metadata = token_metadata_account(&token_metadata);
+    let mut mint = metadata_mint(key, other_metadata.key(), None, &[]);
+    assert!(AssetMetadata::load(&mint.info(), &other_metadata.info()).is_ok());
+    assert!(AssetMetadata::load(&mint.info(), &metadata.info()).is_err());
+
+    // Metadata of another mint, even when pointed to, is rejected too.
+    let mut foreign_metadata = token_metadata_account(&self::token_metadata(Pubkey::new_unique()));
+    let mut mint = metadata_mint(key, foreign_metadata.key(), None, &[]);
+    assert!(AssetMetadata::load(&mint.info(), &foreign_metadata.info()).is_err());
+}
+
+#[test]
+fn rejects_truncated_tlv_entries() {
+    let key = Pubkey::new_unique();
+    let token_metadata = token_metadata(key);
+    let group = Pubkey::new_unique();
+    let mut member = group_member_tlv(key, group, 72);
+    member.truncate(40);
+    let mut mint = metadata_mint(key, key, Some(&token_metadata), &member);
+    let mint = mint.info();
+    assert!(AssetMetadata::load(&mint, &mint).is_err());
+
+    let mut metadata = token_metadata_account(&token_metadata);
+    let mut data = metadata.data().to_vec();
+    data.truncate(data.len() - 1);
+    let mut truncated = AccountBuilder::new()
+        .key(metadata.key())
+        .owner(metadata.owner())
+        .data(data)
+        .build();
+    let mut mint = metadata_mint(key, metadata.key(), None, &[]);
+    assert!(AssetMetadata::load(&mint.info(), &metadata.info()).is_ok());
+    assert!(AssetMetadata::load(&mint.info(), &truncated.info()).is_err());
+}
+
+#[test]
+fn reads_the_token_group_of_members() {
+    let key = Pubkey::new_unique();
+    let token_metadata = token_metadata(key);
+    let group = Pubkey::new_unique();
+    let mut mint = metadata_mint(
+        key,
+        key,
+        Some(&token_metadata),
+        &[
+            group_member_pointer_tlv(key),
+            group_member_tlv(key, group, 72),
+        ]
+        .concat(),
+    );
+    let mint = mint.info();
+    let asset = AssetMetadata::load(&mint, &mint).unwrap();
+    let expected_group = AssetGroup {
+        address: group,
+        verified: true,
+    };
+    assert_eq!(
+        asset,
+        expected_metadata(&token_metadata, Some(expected_group))
+    );
+    assert!(asset.validate_collection_creator(&[group], &[]).is_ok());
+
+    // A member entry naming another mint does not count.
+    let mut member_of_other_mint = metadata_mint(
+        key,
+        key,
+        Some(&token_metadata),
+        &[
+            group_member_pointer_tlv(key),
+            group_member_tlv(Pubkey::new_unique(), group, 72),
+        ]
+        .concat(),
+    );
+    let member_of_other_mint = member_of_other_mint.info();
+    assert_eq!(
+        AssetMetadata::load(&member_of_other_mint, &member_of_other_mint)
+            .unwrap()
+            .group,
+        None
+    );
+}
+
+#[test]
+fn ignores_group_members_without_a_member_pointer() {
+    let key = Pubkey::new_unique();
+    let token_metadata = token_metadata(key);
+    let group = Pubkey::new_unique();
+    let mut mint = metadata_mint(
+        key,
+        key,
+        Some(&token_metadata),
+        &group_member_tlv(key, group, 72),
+    );
+    let mint = mint.info();
+
+    assert_eq!(AssetMetadata::load(&mint, &mint).unwrap().group, None);
+}
+
+#[test]
+fn rejects_group_member_pointers_to_other_accounts() {
+    let key = Pubkey::new_unique();
+    let token_metadata = token_metadata(key);
+    let mut mint = metadata_mint(
+        key,
+        key,
+        Some(&token_metadata),
+        &group_member_pointer_tlv(Pubkey::new_unique()),
+    );
+    let mint = mint.info();
+
+    assert_error(
+        AssetMetadata::load(&mint, &mint),
+        UtilErrors::UnsupportedGroupMemberPointer,
+    );
+}
@@ -0,0 +1,391 @@
+use hpl_utils::{
+    fixtures::{self, TestAccount},
+    merkle_tree::{MerkleTree, MerkleVersion},
+    mpl_token_metadata::state::{Collection, Creator},
+    solana_program::{keccak::hashv, pubkey::Pubkey},
+    validate_merkle_allowlist, AllowlistLeaf, MetadataReader, PolicyFailure, PolicyMatch,
+    ValidationPolicy, ValidationReport,
+};
+
+/// An NFT in a verified `collection`, by a verified and an unverified
+/// creator splitting the royalties 70/30.
+struct Nft {
+    account: TestAccount,
+    collection: Pubkey,
+    verified_creator: Pubkey,
+    unverified_creator: Pubkey,
+    update_authority: Pubkey,
+}
+
+impl Nft {
+    fn new() -> Self {
+        let collection = Pubkey::new_unique();
+        let verified_creator = Pubkey::new_unique();
+        let unverified_creator = Pubkey::new_unique();
+        let update_authority = Pubkey::new_unique();
+        let mut metadata = fixtures::nft_metadata(Pubkey::new_unique(), update_authority);
+        metadata.data.creators = Some(vec![
+            Creator {
+                address: verified_creator,
+                verified: true,
+                share: 70,
+            },
+            Creator {
+                address: unverified_creator,
+                verified: false,
+                share: 30,
+            },
+        ]);
+        metadata.collection = Some(Collection {
+            verified: true,
+            key: collection,
+        });
+        Self {
+            account: fixtures::metadata(&metadata).build(),
+            collection,
+            verified_creator,
+            unverified_creator,
+            update_authority,
+        }
+    }
+
+    fn evaluate(&self, policy: &ValidationPolicy) -> ValidationReport {
+        policy
+            .evaluate(&MetadataReader::new(self.account.data()).unwrap())
+            .unwrap()
+    }
+}
+
+fn matched(matches: Vec<PolicyMatch>) -> ValidationReport {
+    ValidationReport {
+        passed: true,
+        matches,
+        failures: vec![],
+    }
+}
+
+fn failed(failures: Vec<PolicyFailure>) -> ValidationReport {
+    ValidationReport {
+        passed: false,
+        matches: vec![],
+        failures,
+    }
+}
+
+#[test]
+fn collection_must_be_listed_and_verified() {
+    let nft = Nft::new();
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::Collection(vec![
+            Pubkey::new_unique(),
+            nft.collection
+        ])),
+        matched(vec![PolicyMatch::Collection {
+            address: nft.collection
+        }])
+    );
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::Collection(vec![Pubkey::new_unique()])),
+        failed(vec![PolicyFailure::Collection])
+    );
+
+    let mut metadata = fixtures::nft_metadata(Pubkey::new_unique(), nft.update_authority);
+    metadata.collection = Some(Collection {
+        verified: false,
+        key: nft.collection,
+    });
+    let unverified = fixtures::metadata(&metadata).build();
+    assert_eq!(
+        ValidationPolicy::Collection(vec![nft.collection])
+            .evaluate(&MetadataReader::new(unverified.data()).unwrap())
+            .unwrap(),
+        failed(vec![PolicyFailure::Collection])
+    );
+}
+
+#[test]
+fn creator_must_be_listed_and_verified() {
+    let nft = Nft::new();
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::Creator(vec![nft.verified_creator])),
+        matched(vec![PolicyMatch::Creator {
+            address: nft.verified_creator,
+            share: 70
+        }])
+    );
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::Creator(vec![nft.unverified_creator])),
+        failed(vec![PolicyFailure::Creator])
+    );
+}
+
+#[test]
+fn creator_must_hold_the_minimum_share() {
+    let nft = Nft::new();
+    let policy = |min_share| ValidationPolicy::CreatorWithShare {
+        creators: vec![nft.verified_creator],
+        min_share,
+    };
+    assert_eq!(
+        nft.evaluate(&policy(70)),
+        matched(vec![PolicyMatch::Creator {
+            address: nft.verified_creator,
+            share: 70
+        }])
+    );
+    assert_eq!(
+        nft.evaluate(&policy(71)),
+        failed(vec![PolicyFailure::CreatorShare { min_share: 71 }])
+    );
+}
+
+#[test]
+fn all_creators_must_be_verified() {
+    let nft = Nft::new();
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::AllCreatorsVerified(vec![
+            nft.verified_creator
+        ])),
+        matched(vec![PolicyMatch::Creator {
+            address: nft.verified_creator,
+            share: 70
+        }])
+    );
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::AllCreatorsVerified(vec![
+            nft.verified_creator,
+            nft.unverified_creator
+        ])),
+        failed(vec![PolicyFailure::UnverifiedCreator {
+            address: nft.unverified_creator
+        }])
+    );
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::AllCreatorsVerified(vec![])),
+        failed(vec![PolicyFailure::EmptyPolicy])
+    );
+}
+
+#[test]
+fn update_authority_must_be_listed() {
+    let nft = Nft::new();
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::UpdateAuthority(vec![
+            nft.update_authority
+        ])),
+        matched(vec![PolicyMatch::UpdateAuthority {
+            address: nft.update_authority
+        }])
+    );
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::UpdateAuthority(vec![
+            nft.verified_creator
+        ])),
+        failed(vec![PolicyFailure::UpdateAuthority])
+    );
+}
+
+#[test]
+fn all_requires_every_policy() {
+    let nft = Nft::new();
+    let collection = ValidationPolicy::Collection(vec![nft.collection]);
+    let creator = ValidationPolicy::Creator(vec![nft.verified_creator]);
+    let wrong_authority = ValidationPolicy::UpdateAuthority(vec![Pubkey::new_unique()]);
+
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::All(vec![
+            collection.clone(),
+            creator.clone()
+        ])),
+        matched(vec![
+            PolicyMatch::Collection {
+                address: nft.collection
+            },
+            PolicyMatch::Creator {
+                address: nft.verified_creator,
+                share: 70
+            },
+        ])
+    );
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::All(vec![collection, wrong_authority])),
+        failed(vec![PolicyFailure::UpdateAuthority])
+    );
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::All(vec![])),
+        failed(vec![PolicyFailure::EmptyPolicy])
+    );
+}
+
+#[test]
+fn any_requires_one_policy() {
+    let nft = Nft::new();
+    let creator = ValidationPolicy::Creator(vec![nft.verified_creator]);
+    let wrong_collection = ValidationPolicy::Collection(vec![Pubkey::new_unique()]);
+    let wrong_authority = ValidationPolicy::UpdateAuthority(vec![Pubkey::new_unique()]);
+
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::Any(vec![
+            wrong_collection.clone(),
+            creator
+        ])),
+        matched(vec![PolicyMatch::Creator {
+            address: nft.verified_creator,
+            share: 70
+        }])
+    );
+    assert_eq!(
+        nft.evaluate(&ValidationPolicy::Any(vec![
+            wrong_collection,
+            wrong_authority
+        ])),
+        failed(vec![
+            PolicyFailure::Collection,
+            PolicyFailure::UpdateAuthority
+        ])
+    );
+    assert_eq!(nft.evaluate(&ValidationPolicy::Any(vec![])), failed(vec![]));
+}
+
+#[test]
+fn nested_policies_combine_their_reports() {
+    let nft = Nft::new();
+    let collection = ValidationPolicy::Collection(vec![nft.collection]);
+    let authority = ValidationPolicy::UpdateAuthority(vec![nft.update_authority]);
+    let wrong_creator = ValidationPolicy::Creator(vec![nft.unverified_creator]);
+
+    // All(Collection, Any(wrong creator, update authority))
+    let policy = ValidationPolicy::All(vec![
+        collection.clone(),
+        ValidationPolicy::Any(vec![wrong_creator.clone(), authority.clone()]),
+    ]);
+    assert_eq!(
+        nft.evaluate(&policy),
+        matched(vec![
+            PolicyMatch::Collection {
+                address: nft.collection
+            },
+            PolicyMatch::UpdateAuthority {
+                address: nft.update_authority
+            },
+        ])
+    );
+
+    // Any(All(Collection, wrong creator), All(update authority))
+    let policy = ValidationPolicy::Any(vec![
+        ValidationPolicy::All(vec![collection.clone(), wrong_creator.clone()]),
+        ValidationPolicy::All(vec![authority]),
+    ]);
+    assert_eq!(
+        nft.evaluate(&policy),
+        matched(vec![PolicyMatch::UpdateAuthority {
+            address: nft.update_authority
+        }])
+    );
+
+    // An empty All nested in Any does not make it pass.
+    let policy = ValidationPolicy::Any(vec![
+        ValidationPolicy::All(vec![]),
+        ValidationPolicy::All(vec![collection, wrong_creator]),
+    ]);
+    assert_eq!(
+        nft.evaluate(&policy),
+        failed(vec![PolicyFailure::EmptyPolicy, PolicyFailure::Creator])
+    );
+}
+
+#[test]
+fn validate_fails_with_the_report() {
+    let nft = Nft::new();
+    let reader = MetadataReader::new(nft.account.data()).unwrap();
+    assert!(ValidationPolicy::All(vec![]).validate(&reader).is_err());
+    assert!(
+        ValidationPolicy::Collection(vec![nft.collection])
+            .validate(&reader)
+            .unwrap()
+            .passed
+    );
+}
+
+/// The allowlist of `nfts`, keyed by `leaf`.
+fn allowlist(nfts: &[Nft], leaf: AllowlistLeaf, version: MerkleVersion) -> MerkleTree {
+    let values: Vec<[u8; 32]> = nfts
+        .iter()
+        .map(|nft| {
+            let metadata = MetadataReader::new(nft.account.data()).unwrap();
+            match leaf {
+                AllowlistLeaf::Mint => metadata.mint().to_bytes(),
+                AllowlistLeaf::MetadataHash => hashv(&[nft.account.data()]).0,
+            }
+        })
+        .collect();
+    MerkleTree::from_data(version, &values).unwrap()
+}
+
+#[test]
+fn allowlists_prove_their_entries() {
+    let nfts = [Nft::new(), Nft::new(), Nft::new()];
+    for leaf in [AllowlistLeaf::Mint, AllowlistLeaf::MetadataHash] {
+        for version in [MerkleVersion::V1, MerkleVersion::V2] {
+            let tree = allowlist(&nfts, leaf, version);
+            for (index, nft) in nfts.iter().enumerate() {
+                let metadata = MetadataReader::new(nft.account.data()).unwrap();
+                let proof = tree.proof(index).unwrap();
+                let expected = tree.leaf(index).unwrap();
+                assert_eq!(leaf.leaf(&metadata, version), expected);
+                assert_eq!(
+                    validate_merkle_allowlist(&metadata, tree.root(), &proof, leaf, version)
+                        .unwrap(),
+                    expected
+                );
+
+                let policy = ValidationPolicy::MerkleAllowlist {
+                    root: tree.root(),
+                    proof,
+                    leaf,
+                    version,
+                };
+                assert_eq!(
+                    nft.evaluate(&policy),
+                    matched(vec![PolicyMatch::MerkleAllowlist { leaf: expected }])
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn allowlists_reject_wrong_proofs() {
+    let nfts = [Nft::new(), Nft::new(), Nft::new()];
+    let tree = allowlist(&nfts, AllowlistLeaf::Mint, MerkleVersion::V2);
+    let metadata = MetadataReader::new(nfts[0].account.data()).unwrap();
+    let proof = tree.proof(1).unwrap();
+
+    assert!(validate_merkle_allowlist(
+        &metadata,
+        tree.root(),
+        &proof,
+        AllowlistLeaf::Mint,
+        MerkleVersion::V2
+    )
+    .is_err());
+    assert_eq!(
+        nfts[0].evaluate(&ValidationPolicy::MerkleAllowlist {
+            root: tree.root(),
+            proof,
+            leaf: AllowlistLeaf::Mint,
+            version: MerkleVersion::V2,
+        }),
+        failed(vec![PolicyFailure::MerkleAllowlist])
+    );
+
+    // The V1 encoding of a leaf is not part of a V2 allowlist.
+    assert!(validate_merkle_allowlist(
+        &metadata,
+        tree.root(),
+        &tree.proof(0).unwrap(),
+        AllowlistLeaf::Mint,
+        MerkleVersion::V1
+    )
+    .is_err());
+}